@@ -4,10 +4,10 @@ _tldr() {
     local cur="${COMP_WORDS[COMP_CWORD]}"
     local prev="${COMP_WORDS[COMP_CWORD-1]}"
 
-    local opts="-u -l -a -i -r -p -L -o -c -R -q -v -h \
-    --update --list --list-all --list-platforms --list-languages \
-    --info --render --clean-cache --gen-config --config-path --platform \
-    --language --offline --compact --no-compact --raw --no-raw --quiet \
+    local opts="-u -l -a -i -r -p -L -o -c -R -P -q -y -v -h \
+    --update --import --list --new --list-all --count --list-platforms --list-languages \
+    --info --verify --render --stdin-render --strict --clean-cache --prune-languages --gen-config --commented --check-config --config-path --gen-completions --pages-dir --platform --no-common \
+    --language --offline --no-auto-update --strict-offline --compact --no-compact --raw --no-raw --raw-resolved --multi --copy --diff --url --edit-link --open --dry-run --where --edit --select-format --interactive --no-other-platforms --no-title --pager --quiet --verbose --log-timestamps --log-format --format --yes \
     --color --config --version --help"
 
     if [[ $cur == -* ]]; then
@@ -16,13 +16,23 @@ _tldr() {
     fi
 
     case $prev in
-        -r|--render|--config)
+        -r|--render|--config|--import)
             mapfile -t COMPREPLY < <(compgen -f -- "$cur");;
+        --pages-dir)
+            mapfile -t COMPREPLY < <(compgen -d -- "$cur");;
+        --gen-completions)
+            mapfile -t COMPREPLY < <(compgen -W "bash elvish fish powershell zsh" -- "$cur");;
+        --copy)
+            COMPREPLY=();;
         --color)
             mapfile -t COMPREPLY < <(compgen -W "auto always never" -- "$cur");;
+        --log-format)
+            mapfile -t COMPREPLY < <(compgen -W "text json" -- "$cur");;
+        --format)
+            mapfile -t COMPREPLY < <(compgen -W "text json tsv" -- "$cur");;
         -p|--platform)
             mapfile -t COMPREPLY < <(compgen -W "$(tldr --offline --list-platforms 2> /dev/null)" -- "$cur");;
-        -L|--language)
+        -L|--language|--diff)
             mapfile -t COMPREPLY < <(compgen -W "$(tldr --offline --list-languages 2> /dev/null)" -- "$cur");;
         *)
             mapfile -t COMPREPLY < <(compgen -W "$(tldr --offline --list-all 2> /dev/null)" -- "$cur");;
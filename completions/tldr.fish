@@ -1,6 +1,10 @@
-complete -c tldr -s r -l render -d "Render the specified markdown file" -r
+complete -c tldr -s r -l render -d "Render the specified markdown files" -r
+complete -c tldr -l stdin-render -d "Render pages from standard input (a title starts a new page)"
+complete -c tldr -l strict -d "Stop at the first file that cannot be rendered"
+complete -c tldr -l pages-dir -d "Use pages from a directory with the same layout as the cache" -x -a "(__fish_complete_directories)"
 complete -c tldr -s p -l platform -d "Specify the platform to use (linux, osx, windows, etc.)" -x -a \
     "(tldr --offline --list-platforms 2> /dev/null)"
+complete -c tldr -l no-common -d "Do not list or search pages from the common platform (unless no other page is found)"
 complete -c tldr -s L -l language -d "Specify the languages to use" -x -a \
     "(tldr --offline --list-languages 2> /dev/null)"
 complete -c tldr -l color -d "Specify when to enable color" -x -a "
@@ -10,20 +14,58 @@ complete -c tldr -l color -d "Specify when to enable color" -x -a "
 "
 complete -c tldr -l config -d "Specify an alternative path to the config file" -r
 complete -c tldr -s u -l update -d "Update the cache"
+complete -c tldr -l import -d "Install pages from a local archive (tldr-pages.LANGUAGE.zip)" -r
 complete -c tldr -s l -l list -d "List all pages in the current platform"
+complete -c tldr -l new -d "List only pages added in the last update (with --list)"
 complete -c tldr -s a -l list-all -d "List all pages"
+complete -c tldr -l count -d "Print the number of pages in the current platform, or the number of variants of PAGE"
 complete -c tldr -s a -l list-platforms -d "List available platforms"
 complete -c tldr -s a -l list-languages -d "List installed languages"
-complete -c tldr -s i -l info -d "Show cache information (path, age, installed languages and the number of pages)"
+complete -c tldr -s i -l info -d "Show cache information (path, age, installed languages, the number of pages and disk usage)"
+complete -c tldr -l verify -d "Check if all pages in the cache are readable and valid"
 complete -c tldr -l clean-cache -d "Clean the cache"
+complete -c tldr -l prune-languages -d "Delete languages that are not in the config"
 complete -c tldr -l gen-config -d "Print the default config"
+complete -c tldr -l commented -d "Add comments that explain every option to the default config"
+complete -c tldr -l check-config -d "Check the config file for errors and show the resolved options"
 complete -c tldr -l config-path -d "Print the default config path and create the config directory"
+complete -c tldr -l gen-completions -d "Print a completion script for the specified shell" -x -a "bash elvish fish powershell zsh"
 complete -c tldr -s o -l offline -d "Do not update the cache, even if it is stale"
+complete -c tldr -l no-auto-update -d "Do not update the cache if it is stale (pages are still downloaded if the cache is empty)"
+complete -c tldr -l strict-offline -d "Exit with an error instead of showing a warning if the cache is stale (for --offline)"
 complete -c tldr -s c -l compact -d "Strip empty lines from output"
 complete -c tldr -l no-compact -d "Do not strip empty lines from output (overrides --compact)"
 complete -c tldr -s R -l raw -d "Print pages in raw markdown instead of rendering them"
 complete -c tldr -l no-raw -d "Render pages instead of printing raw file contents (overrides --raw)"
+complete -c tldr -l raw-resolved -d "Print pages in raw markdown, but resolve placeholder alternatives in examples"
+complete -c tldr -l multi -d "Show every PAGE argument as a separate page"
+complete -c tldr -l copy -d "Copy the command from the Nth example of the page to the clipboard" -x
+complete -c tldr -l diff -d "Compare a page in two languages" -x -a \
+    "(tldr --offline --list-languages 2> /dev/null)"
+complete -c tldr -l url -d "Print only the \"More information\" URL of the page"
+complete -c tldr -l edit-link -d "Print the URL of the page in the tldr repository on GitHub"
+complete -c tldr -l open -d "Open the \"More information\" URL of the page in the default browser"
+complete -c tldr -l dry-run -d "Print the URL instead of opening it (for --open), or show which languages would be updated (for --update)"
+complete -c tldr -l where -d "Print the paths of the page instead of rendering it"
+complete -c tldr -l edit -d "Open the page in a text editor"
+complete -c tldr -l select-format -d "Print one example per line as \"DESCRIPTION<TAB>COMMAND\" (e.g. for fzf)"
+complete -c tldr -l interactive -d "Ask which page to show if pages with the same name are found for other platforms"
+complete -c tldr -l no-other-platforms -d "Do not list pages with the same name from other platforms"
+complete -c tldr -l no-title -d "Do not show the title of the page"
+complete -c tldr -s P -l pager -d "Display the page in a pager"
 complete -c tldr -s q -l quiet -d "Suppress status messages and warnings"
+complete -c tldr -l verbose -d "Print additional messages that are useful for debugging"
+complete -c tldr -l log-timestamps -d "Prefix status messages, warnings and errors with the current time (UTC)"
+complete -c tldr -l log-format -d "Specify the format of status messages, warnings and debug messages" -x -a "
+    text\t'Human-readable messages'
+    json\t'One JSON object per message'
+"
+complete -c tldr -l format -d "Specify the format of error messages, --info and --list-languages" -x -a "
+    text\t'Human-readable output'
+    json\t'JSON objects (for errors and --info)'
+    tsv\t'Tab-separated values (for --info)'
+"
+complete -c tldr -s y -l yes -d "Do not ask for confirmation"
 complete -c tldr -s v -l version -d "Print version"
 complete -c tldr -s h -l help -d "Print help"
 complete -c tldr -f -a "(tldr --offline --list-all 2> /dev/null)"
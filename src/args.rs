@@ -1,8 +1,33 @@
+use std::io::{self, Write};
 use std::path::PathBuf;
 
-use clap::{ArgAction, ColorChoice, Parser};
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgGroup, ColorChoice, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use clap_complete::Shell;
 
-const DEFAULT_PLATFORM: &str = if cfg!(target_os = "linux") {
+use crate::error::Result;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable output.
+    #[default]
+    Text,
+    /// JSON objects (for errors, --info and --list-languages).
+    Json,
+    /// Tab-separated values (for --info and --list-languages).
+    Tsv,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable messages.
+    #[default]
+    Text,
+    /// One JSON object per message.
+    Json,
+}
+
+pub const DEFAULT_PLATFORM: &str = if cfg!(target_os = "linux") {
     "linux"
 } else if cfg!(target_os = "macos") {
     "osx"
@@ -20,6 +45,12 @@ const DEFAULT_PLATFORM: &str = if cfg!(target_os = "linux") {
     "common"
 };
 
+/// Completions that use the cache (fish merges these with the generated ones).
+const FISH_DYNAMIC_COMPLETIONS: &str = "\
+complete -c tldr -s p -l platform -x -a \"(tldr --offline --list-platforms 2> /dev/null)\"
+complete -c tldr -s L -l language -x -a \"(tldr --offline --list-languages 2> /dev/null)\"
+complete -c tldr -f -a \"(tldr --offline --list-all 2> /dev/null)\"";
+
 const AFTER_HELP: &str = if cfg!(target_os = "windows") {
     // Man pages are not available on Windows.
     "See https://tldr.sh/tlrc for more information."
@@ -38,26 +69,51 @@ const AFTER_HELP: &str = if cfg!(target_os = "windows") {
     help_template = "{before-help}{name} {version}\n\
     {about-with-newline}\n\
     {usage-heading} {usage}\n\n\
-    {all-args}{after-help}"
+    {all-args}{after-help}",
+    // --dry-run works with both of these.
+    group(ArgGroup::new("dry_run_target").args(["open", "update"]).multiple(true))
 )]
 #[allow(clippy::manual_non_exhaustive)]
 pub struct Cli {
-    /// The tldr page to show.
+    /// The tldr page to show. Multiple words are joined with hyphens
+    /// ("git commit" shows "git-commit"), unless --multi is used.
     #[arg(group = "operations", required = true)]
     pub page: Vec<String>,
 
-    /// Update the cache.
+    /// Update the cache. With --language, only those languages are updated
+    /// and the next automatic update is not postponed.
     #[arg(short, long, group = "operations")]
     pub update: bool,
 
+    /// Install pages from a local archive (tldr-pages.LANGUAGE.zip).
+    #[arg(long, group = "operations", value_name = "FILE")]
+    pub import: Option<PathBuf>,
+
     /// List all pages in the current platform.
     #[arg(short, long, group = "operations")]
     pub list: bool,
 
+    /// List only pages added in the last update (with --list).
+    #[arg(long, requires = "list")]
+    pub new: bool,
+
     /// List all pages.
     #[arg(short = 'a', long, group = "operations")]
     pub list_all: bool,
 
+    /// Print the number of pages in the current platform, or the number of platform and
+    /// language variants of PAGE.
+    #[arg(
+        long,
+        group = "operations",
+        value_name = "PAGE",
+        num_args = 0..,
+        conflicts_with_all = [
+            "multi", "copy", "diff", "select_format", "url", "open", "where_", "edit", "edit_link"
+        ]
+    )]
+    pub count: Option<Vec<String>>,
+
     /// List available platforms.
     #[arg(long, group = "operations")]
     pub list_platforms: bool,
@@ -66,30 +122,77 @@ pub struct Cli {
     #[arg(long, group = "operations")]
     pub list_languages: bool,
 
-    /// Show cache information (path, age, installed languages and the number of pages).
+    /// Show cache information (path, age, installed languages, the number of pages and disk usage).
     #[arg(short, long, group = "operations")]
     pub info: bool,
 
-    /// Render the specified markdown file.
-    #[arg(short, long, group = "operations", value_name = "FILE")]
-    pub render: Option<PathBuf>,
+    /// Check if all pages in the cache are readable and valid.
+    #[arg(long, group = "operations")]
+    pub verify: bool,
+
+    /// Render the specified markdown files.
+    #[arg(short, long, group = "operations", value_name = "FILE", num_args = 1..)]
+    pub render: Vec<PathBuf>,
+
+    /// Render pages from standard input (a title starts a new page).
+    #[arg(long, group = "operations")]
+    pub stdin_render: bool,
+
+    /// Stop at the first file that cannot be rendered (when rendering multiple files).
+    #[arg(long, requires = "render")]
+    pub strict: bool,
 
     /// Clean the cache.
     #[arg(long, group = "operations")]
     pub clean_cache: bool,
 
+    /// Delete languages that are not in the config.
+    #[arg(long, group = "operations")]
+    pub prune_languages: bool,
+
     /// Print the default config.
     #[arg(long, group = "operations")]
     pub gen_config: bool,
 
+    /// Add comments that explain every option to the default config (with --gen-config).
+    #[arg(long, requires = "gen_config")]
+    pub commented: bool,
+
+    /// Check the config file for errors and show the resolved options.
+    #[arg(long, group = "operations")]
+    pub check_config: bool,
+
     /// Print the default config path and create the config directory.
     #[arg(long, group = "operations")]
     pub config_path: bool,
 
+    /// Print a completion script for the specified shell.
+    #[arg(long, group = "operations", value_name = "SHELL")]
+    pub gen_completions: Option<Shell>,
+
+    /// Use pages from a directory with the same layout as the cache (e.g. pages.en/common/tar.md).
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["update", "import", "clean_cache", "prune_languages"]
+    )]
+    pub pages_dir: Option<PathBuf>,
+
     /// Specify the platform to use (linux, osx, windows, etc.).
+    ///
+    /// Multiple platforms can be given as a comma-separated list, in order of priority.
+    /// "auto" detects WSL as windows, and uses the default platform otherwise.
     #[arg(short, long, default_value = DEFAULT_PLATFORM)]
     pub platform: String,
 
+    /// Do not list or search pages from the common platform (unless no other page is found).
+    #[arg(long)]
+    pub no_common: bool,
+
+    /// Whether the platform was specified on the command line (and is not the default).
+    #[arg(skip)]
+    pub platform_is_explicit: bool,
+
     /// Specify the languages to use.
     #[arg(short = 'L', long = "language", value_name = "LANGUAGE_CODE")]
     pub languages: Option<Vec<String>>,
@@ -98,6 +201,15 @@ pub struct Cli {
     #[arg(short, long)]
     pub offline: bool,
 
+    /// Do not update the cache if it is stale. Unlike --offline, pages are still downloaded
+    /// if the cache is empty.
+    #[arg(long)]
+    pub no_auto_update: bool,
+
+    /// Exit with an error instead of showing a warning if the cache is stale (for --offline).
+    #[arg(long, requires = "offline")]
+    pub strict_offline: bool,
+
     /// Strip empty lines from output.
     #[arg(short, long)]
     pub compact: bool,
@@ -114,10 +226,102 @@ pub struct Cli {
     #[arg(long)]
     pub no_raw: bool,
 
+    /// Print pages in raw markdown, but resolve placeholder alternatives in examples.
+    #[arg(long, conflicts_with = "no_raw")]
+    pub raw_resolved: bool,
+
+    /// Show every PAGE argument as a separate page (e.g. "tldr --multi tar zip").
+    #[arg(long)]
+    pub multi: bool,
+
+    /// Copy the command from the Nth example of the page to the clipboard.
+    #[arg(long, value_name = "N", conflicts_with = "multi")]
+    pub copy: Option<usize>,
+
+    /// Compare a page in two languages (e.g. "tldr --diff en de tar").
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["LANG1", "LANG2"],
+        conflicts_with_all = ["multi", "copy"]
+    )]
+    pub diff: Vec<String>,
+
+    /// Print only the "More information" URL of the page.
+    #[arg(long, conflicts_with_all = ["multi", "copy", "diff", "select_format"])]
+    pub url: bool,
+
+    /// Print the URL of the page in the tldr repository on GitHub (e.g. to edit it).
+    #[arg(
+        long,
+        conflicts_with_all = ["multi", "copy", "diff", "select_format", "url", "open", "where_", "edit"]
+    )]
+    pub edit_link: bool,
+
+    /// Open the "More information" URL of the page in the default browser.
+    #[arg(long, conflicts_with_all = ["multi", "copy", "diff", "select_format", "url"])]
+    pub open: bool,
+
+    /// Print the URL instead of opening it (for --open), or show which languages would be
+    /// updated without downloading them (for --update).
+    #[arg(long, requires = "dry_run_target")]
+    pub dry_run: bool,
+
+    /// Print the paths of the page instead of rendering it.
+    #[arg(long = "where", conflicts_with_all = ["copy", "diff", "select_format", "url", "open"])]
+    pub where_: bool,
+
+    /// Open the page in a text editor ($VISUAL or $EDITOR).
+    #[arg(long, conflicts_with_all = ["multi", "copy", "diff", "select_format", "url", "open"])]
+    pub edit: bool,
+
+    /// Print one example per line as "DESCRIPTION<TAB>COMMAND" (e.g. for fzf).
+    #[arg(long)]
+    pub select_format: bool,
+
+    /// Ask which page to show if pages with the same name are found for other platforms.
+    #[arg(
+        long,
+        conflicts_with_all = ["no_other_platforms", "select_format", "url", "open", "where_", "edit"]
+    )]
+    pub interactive: bool,
+
+    /// Do not list pages with the same name from other platforms.
+    #[arg(long)]
+    pub no_other_platforms: bool,
+
+    /// Do not show the title of the page.
+    #[arg(long)]
+    pub no_title: bool,
+
+    /// Display the page in a pager ($PAGER or less -R).
+    #[arg(short = 'P', long)]
+    pub pager: bool,
+
     /// Suppress status messages and warnings.
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "verbose")]
     pub quiet: bool,
 
+    /// Print additional messages that are useful for debugging.
+    #[arg(long)]
+    pub verbose: bool,
+
+    /// Prefix status messages, warnings and errors with the current time (UTC).
+    #[arg(long)]
+    pub log_timestamps: bool,
+
+    /// Specify the format of status messages, warnings and debug messages.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t)]
+    pub log_format: LogFormat,
+
+    /// Specify the format of error messages, --info and --list-languages.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t)]
+    pub format: OutputFormat,
+
+    /// Do not ask for confirmation.
+    #[arg(short, long)]
+    pub yes: bool,
+
     /// Specify when to enable color.
     #[arg(long, value_name = "WHEN", default_value_t = ColorChoice::default())]
     pub color: ColorChoice,
@@ -130,3 +334,27 @@ pub struct Cli {
     #[arg(short, long, action = ArgAction::Version)]
     version: (),
 }
+
+impl Cli {
+    /// Parse command-line arguments, exiting on error.
+    pub fn parse_args() -> Self {
+        let matches = Self::command().get_matches();
+        let mut cli = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        cli.platform_is_explicit =
+            matches.value_source("platform") == Some(ValueSource::CommandLine);
+        cli
+    }
+
+    /// Print a completion script for the specified shell.
+    pub fn print_completions(shell: Shell) -> Result<()> {
+        // Write to a buffer first, generate() panics on write errors (e.g. a broken pipe).
+        let mut buf = vec![];
+        clap_complete::generate(shell, &mut Self::command(), "tldr", &mut buf);
+
+        if shell == Shell::Fish {
+            writeln!(buf, "{FISH_DYNAMIC_COMPLETIONS}")?;
+        }
+        io::stdout().write_all(&buf)?;
+        Ok(())
+    }
+}
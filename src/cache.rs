@@ -1,22 +1,68 @@
-use std::collections::{BTreeMap, HashMap};
-use std::ffi::OsString;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fmt::Write as _;
 use std::fs::{self, File};
-use std::io::{self, BufWriter, Cursor, Write};
+use std::io::{self, BufWriter, Cursor, Read, Write};
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::sync::atomic::Ordering::Relaxed;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use once_cell::unsync::OnceCell;
 use yansi::Paint;
 use zip::ZipArchive;
 
+use crate::args::OutputFormat;
 use crate::config::Config;
-use crate::error::{Error, Result};
-use crate::util::{self, info_end, info_start, infoln, warnln, Dedup};
+use crate::error::{Error, ErrorKind, IoPathContext, Result};
+use crate::output::PageRenderer;
+use crate::util::{self, debugln, info_end, info_start, infoln, warnln, Dedup, PagePathExt};
 
 pub const ENGLISH_DIR: &str = "pages.en";
+/// The directory where archives are kept if `cache.keep_archives` is enabled.
+const ARCHIVES_DIR: &str = "archives";
+/// The file that stores `ETag`s of downloaded archives.
+const ETAGS_FILE: &str = "tldr.etags";
+/// The file that stores the time of the last update (seconds since the Unix epoch).
+const LAST_UPDATE_FILE: &str = "last_update";
+/// The file that lists platforms in `pages.en`, so that the directory does not have to be read on every run.
+const PLATFORM_INDEX_FILE: &str = "platforms";
+/// The file that lists pages added in the last update that changed any pages (e.g. `pages.en/linux/tar.md`).
+const NEW_PAGES_FILE: &str = "new_pages";
 const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), '/', env!("CARGO_PKG_VERSION"));
 
 type PagesArchive = ZipArchive<Cursor<Vec<u8>>>;
+/// The number of extracted, new and unchanged pages.
+type PageCounts = (i32, i32, i32);
+
+/// The result of updating a single language.
+#[derive(Default)]
+enum LangStatus {
+    /// The installed pages are already up to date.
+    #[default]
+    UpToDate,
+    /// The archive was extracted (the total number of pages, the number of new pages
+    /// and the number of pages that did not change).
+    Updated(i32, i32, i32),
+    /// The archive could not be downloaded or verified.
+    Failed,
+}
+
+/// The summary of updating a single language, shown in a table after the update.
+#[derive(Default)]
+struct LangReport {
+    downloaded_bytes: u64,
+    status: LangStatus,
+    /// Pages that did not exist before the update (relative to the cache directory).
+    new_pages: Vec<String>,
+}
+
+/// An HTTP agent with the maximum size of downloaded files.
+struct Agent {
+    inner: ureq::Agent,
+    max_download_bytes: u64,
+}
 
 pub struct Cache<'a> {
     dir: &'a Path,
@@ -35,7 +81,15 @@ impl<'a> Cache<'a> {
 
     /// Get the default path to the cache.
     pub fn locate() -> PathBuf {
-        dirs::cache_dir().unwrap().join(env!("CARGO_PKG_NAME"))
+        env::var_os("TLDR_CACHE_DIR")
+            .filter(|x| !x.is_empty())
+            .map_or_else(
+                || {
+                    util::base_dir("XDG_CACHE_HOME", dirs::cache_dir(), ".cache")
+                        .join(env!("CARGO_PKG_NAME"))
+                },
+                PathBuf::from,
+            )
     }
 
     /// Return `true` if the specified subdirectory exists in the cache.
@@ -43,50 +97,111 @@ impl<'a> Cache<'a> {
         self.dir.join(sd).is_dir()
     }
 
+    /// Create an HTTP agent used for downloading pages.
+    fn agent(cfg: &Config) -> Agent {
+        Agent {
+            inner: ureq::Agent::config_builder()
+                .user_agent(USER_AGENT)
+                .timeout_global(cfg.http_timeout())
+                .build()
+                .into(),
+            max_download_bytes: cfg.cache.max_download_bytes,
+        }
+    }
+
+    /// Read the body of a response, failing if it is larger than `cache.max_download_bytes`.
+    fn read_body(agent: &Agent, url: &str, body: &mut ureq::Body) -> Result<Vec<u8>> {
+        body.with_config()
+            .limit(agent.max_download_bytes)
+            .read_to_vec()
+            .map_err(|e| match e {
+                ureq::Error::BodyExceedsLimit(limit) => Error::download_limit(url, limit),
+                e => e.into(),
+            })
+    }
+
+    /// Return `true` if pages were downloaded from a mirror (and not only fetched on demand).
+    pub fn is_downloaded(&self) -> bool {
+        self.dir.join("tldr.sha256sums").is_file()
+    }
+
     /// Send a GET request with the provided agent and return the response body.
-    fn get_asset(agent: &ureq::Agent, url: &str) -> Result<Vec<u8>> {
-        info_start!("downloading '{}'... ", url.split('/').last().unwrap());
+    fn get_asset(agent: &Agent, url: &str) -> Result<Vec<u8>> {
+        // Without `If-None-Match`, the server never responds with 304 Not Modified.
+        Ok(Self::get_asset_if_modified(agent, url, None)?
+            .map(|(bytes, _)| bytes)
+            .unwrap_or_default())
+    }
 
-        let mut resp = match agent.get(url).call() {
+    /// Send a GET request with the provided agent and return the response body along with its `ETag`.
+    ///
+    /// If `etag` is provided, it is sent in the `If-None-Match` header. `None` is returned
+    /// when the server responds with 304 Not Modified.
+    fn get_asset_if_modified(
+        agent: &Agent,
+        url: &str,
+        etag: Option<&str>,
+    ) -> Result<Option<(Vec<u8>, Option<String>)>> {
+        debugln!("GET {url}");
+        info_start!("downloading '{}'... ", url.split('/').next_back().unwrap());
+
+        let mut req = agent.inner.get(url);
+        if let Some(etag) = etag {
+            req = req.header("If-None-Match", etag);
+        }
+
+        let mut resp = match req.call() {
             Ok(r) => r,
             Err(e) => {
                 info_end!("{}", "FAILED".red().bold());
                 return Err(e.into());
             }
         };
-        let body = resp.body_mut();
-        let bytes = match body.with_config().limit(1_000_000_000).read_to_vec() {
+
+        if resp.status() == 304 {
+            info_end!("{}", "not modified".green().bold());
+            return Ok(None);
+        }
+
+        let new_etag = resp
+            .headers()
+            .get("ETag")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        let bytes = match Self::read_body(agent, url, resp.body_mut()) {
             Ok(v) => v,
             Err(e) => {
                 info_end!("{}", "FAILED".red().bold());
-                return Err(e.into());
+                return Err(e);
             }
         };
 
-        #[allow(clippy::cast_precision_loss)]
-        let dl_kib = bytes.len() as f64 / 1024.0;
-        if dl_kib < 1024.0 {
-            info_end!("{:.02} KiB", dl_kib.green().bold());
-        } else {
-            info_end!("{:.02} MiB", (dl_kib / 1024.0).green().bold());
-        }
+        info_end!("{}", util::size_fmt(bytes.len() as u64).green().bold());
 
-        Ok(bytes)
+        Ok(Some((bytes, new_etag)))
     }
 
-    /// Download tldr pages archives for directories that are out of date and update the checksum file.
+    /// Download tldr pages archives for directories that are out of date, extract them
+    /// and update the checksum file.
+    ///
+    /// Archives are extracted one at a time as soon as they are verified, so that only
+    /// one archive is kept in memory.
+    ///
+    /// Returns the number of extracted, new and unchanged pages for every updated language directory.
+    /// Languages that could not be updated are returned in the second element of the tuple.
+    /// The size of downloaded archives and the status of every language is recorded in `report`.
+    /// If `delay` is set, it is waited before every archive request except the first one.
     fn download_and_verify(
         &self,
+        agent: &Agent,
         mirror: &str,
         languages: &[String],
-    ) -> Result<BTreeMap<String, PagesArchive>> {
-        let agent = ureq::Agent::config_builder()
-            .user_agent(USER_AGENT)
-            .timeout_global(Some(Duration::from_secs(5)))
-            .build()
-            .into();
-
-        let sums = Self::get_asset(&agent, &format!("{mirror}/tldr.sha256sums"))?;
+        keep_archives: bool,
+        delay: Option<Duration>,
+        report: &mut BTreeMap<String, LangReport>,
+    ) -> Result<(BTreeMap<String, PageCounts>, Vec<String>)> {
+        let sums = Self::get_asset(agent, &format!("{mirror}/tldr.sha256sums"))?;
         let sums_str = String::from_utf8_lossy(&sums);
         let sum_map = Self::parse_sumfile(&sums_str)?;
 
@@ -94,7 +209,13 @@ impl<'a> Cache<'a> {
         let old_sums = fs::read_to_string(&old_sumfile_path).unwrap_or_default();
         let old_sum_map = Self::parse_sumfile(&old_sums).unwrap_or_default();
 
-        let mut langdir_archive_map = BTreeMap::new();
+        let etags_path = self.dir.join(ETAGS_FILE);
+        let old_etags = fs::read_to_string(&etags_path).unwrap_or_default();
+        let mut etag_map = Self::parse_etags(&old_etags);
+
+        let mut counts = BTreeMap::new();
+        let mut failed = vec![];
+        let mut sent_request = false;
 
         for lang in languages {
             let lang = &**lang;
@@ -103,34 +224,263 @@ impl<'a> Cache<'a> {
                 continue;
             };
 
+            let lang_report = report.entry(lang.to_string()).or_default();
             let lang_dir = format!("pages.{lang}");
-            if Some(sum) == old_sum_map.get(lang) && self.subdir_exists(&lang_dir) {
-                infoln!("'pages.{lang}' is up to date");
+            if self.is_up_to_date(lang, sum, &old_sum_map) {
+                debugln!("'{lang_dir}' is up to date");
+                lang_report.status = LangStatus::UpToDate;
                 continue;
             }
 
-            let archive = Self::get_asset(&agent, &format!("{mirror}/tldr-pages.{lang}.zip"))?;
-            info_start!("validating sha256sums... ");
-            let actual_sum = util::sha256_hexdigest(&archive);
+            let kept_archive = self.kept_archive_path(lang);
+            let kept = keep_archives
+                .then(|| Self::read_kept_archive(&kept_archive, sum))
+                .flatten();
+
+            let (archive, new_etag) = if let Some(archive) = kept {
+                infoln!("using the kept archive for '{lang_dir}'");
+                (archive, etag_map.get(lang).cloned())
+            } else {
+                // Only send the ETag if the pages are actually there - otherwise,
+                // a 304 response would leave us without the directory.
+                let old_etag = etag_map
+                    .get(lang)
+                    .filter(|_| self.subdir_exists(&lang_dir))
+                    .map(String::as_str);
+
+                if let Some(delay) = delay.filter(|_| sent_request) {
+                    debugln!("waiting {} ms before the next download", delay.as_millis());
+                    thread::sleep(delay);
+                }
+                sent_request = true;
+
+                let keep_path = keep_archives.then_some(&*kept_archive);
+                match Self::download_lang_archive(
+                    agent,
+                    mirror,
+                    lang,
+                    sum,
+                    old_etag,
+                    keep_path,
+                    &mut lang_report.downloaded_bytes,
+                ) {
+                    Ok(Some(x)) => x,
+                    Ok(None) => {
+                        debugln!("'{lang_dir}' is up to date");
+                        lang_report.status = LangStatus::UpToDate;
+                        continue;
+                    }
+                    Err(e) => {
+                        // Do not abort the whole update, other languages can still be installed.
+                        warnln!("could not update '{lang_dir}': {e}");
+                        lang_report.status = LangStatus::Failed;
+                        failed.push(lang.to_string());
+                        continue;
+                    }
+                }
+            };
 
-            if sum != &actual_sum {
-                info_end!("{}", "FAILED".red().bold());
-                return Err(Error::new(format!(
-                    "SHA256 sum mismatch!\n\
-                    expected : {sum}\n\
-                    got      : {actual_sum}"
-                )));
+            // The archive is dropped after extraction, before the next one is downloaded.
+            match self.extract_archive(&lang_dir, archive, &mut lang_report.new_pages) {
+                Ok((n, n_new, n_unchanged)) => {
+                    lang_report.status = LangStatus::Updated(n, n_new, n_unchanged);
+                    counts.insert(lang_dir, (n, n_new, n_unchanged));
+                }
+                Err(e) => {
+                    warnln!("could not update '{lang_dir}': {e}");
+                    lang_report.status = LangStatus::Failed;
+                    failed.push(lang.to_string());
+                    continue;
+                }
+            }
+
+            // Only remember the ETag once the pages are extracted, otherwise
+            // the archive would never be downloaded again.
+            if let Some(new_etag) = new_etag {
+                etag_map.insert(lang, new_etag);
+            } else {
+                etag_map.remove(lang);
             }
+        }
+
+        fs::create_dir_all(self.dir).path_context(self.dir)?;
 
-            info_end!(" {}", "OK".green().bold());
+        Self::write_sumfile(
+            &old_sumfile_path,
+            &sums_str,
+            &old_sum_map,
+            languages,
+            &failed,
+        )?;
 
-            langdir_archive_map.insert(lang_dir, ZipArchive::new(Cursor::new(archive))?);
+        let mut etags_file = BufWriter::new(File::create(&etags_path).path_context(&etags_path)?);
+        for (lang, etag) in etag_map {
+            writeln!(etags_file, "{lang} {etag}")?;
         }
+        etags_file.flush()?;
 
-        fs::create_dir_all(self.dir)?;
-        File::create(&old_sumfile_path)?.write_all(&sums)?;
+        Ok((counts, failed))
+    }
 
-        Ok(langdir_archive_map)
+    /// Check if `lang` is installed and its sum in the old checksum file is `sum`.
+    fn is_up_to_date(&self, lang: &str, sum: &str, old_sum_map: &HashMap<&str, &str>) -> bool {
+        old_sum_map.get(lang) == Some(&sum) && self.subdir_exists(&format!("pages.{lang}"))
+    }
+
+    /// Download only the checksum file from `mirror` and print which of `languages`
+    /// would be updated. Nothing is written to the cache.
+    fn preview_mirror(&self, agent: &Agent, mirror: &str, languages: &[String]) -> Result<()> {
+        let sums = Self::get_asset(agent, &format!("{mirror}/tldr.sha256sums"))?;
+        let sums_str = String::from_utf8_lossy(&sums);
+        let sum_map = Self::parse_sumfile(&sums_str)?;
+
+        let old_sums = fs::read_to_string(self.dir.join("tldr.sha256sums")).unwrap_or_default();
+        let old_sum_map = Self::parse_sumfile(&old_sums).unwrap_or_default();
+
+        let mut stdout = io::stdout().lock();
+        for lang in languages {
+            let Some(sum) = sum_map.get(&**lang) else {
+                // Nonexistent languages are skipped on update.
+                continue;
+            };
+
+            if self.is_up_to_date(lang, sum, &old_sum_map) {
+                writeln!(stdout, "{lang:5} : {}", "up to date".green().bold())?;
+            } else {
+                writeln!(stdout, "{lang:5} : {}", "would update".yellow().bold())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Show which of `languages` would be updated, without downloading any archives (--dry-run).
+    pub fn preview_update(&self, cfg: &Config, languages: &[String]) -> Result<()> {
+        let mirrors = cfg.mirrors();
+        let mut languages = languages.to_vec();
+        languages.sort_unstable();
+        languages.dedup();
+        let agent = Self::agent(cfg);
+
+        for (i, mirror) in mirrors.iter().enumerate() {
+            match self.preview_mirror(&agent, mirror, &languages) {
+                // Try the next mirror if there is one.
+                Err(e) if matches!(e.kind, ErrorKind::Download) && i + 1 != mirrors.len() => {
+                    warnln!("{e}");
+                }
+                res => return res,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the new checksum file to `path`.
+    ///
+    /// The sumfile should only reflect languages that are actually installed (possibly
+    /// from another mirror). Keep the old sums of languages that failed or were not updated
+    /// from this mirror, so that they are retried next time.
+    fn write_sumfile(
+        path: &Path,
+        sums: &str,
+        old_sum_map: &HashMap<&str, &str>,
+        languages: &[String],
+        failed: &[String],
+    ) -> Result<()> {
+        let mut sumfile = BufWriter::new(File::create(path).path_context(path)?);
+
+        for l in sums.lines() {
+            let lang = l
+                .split_whitespace()
+                .nth(1)
+                .and_then(|path| path.strip_prefix("tldr-pages."))
+                .and_then(|path| path.strip_suffix(".zip"));
+
+            let old_sum = lang.and_then(|lang| {
+                let updated =
+                    languages.iter().any(|x| x == lang) && !failed.iter().any(|x| x == lang);
+                if updated {
+                    None
+                } else {
+                    old_sum_map.get(lang)
+                }
+            });
+
+            // Without an old sum, the new one would make a failed language look up to date.
+            let failed_without_sum = lang.is_some_and(|lang| failed.iter().any(|x| x == lang));
+
+            if let (Some(lang), Some(old_sum)) = (lang, old_sum) {
+                writeln!(sumfile, "{old_sum}  tldr-pages.{lang}.zip")?;
+            } else if !failed_without_sum {
+                writeln!(sumfile, "{l}")?;
+            }
+        }
+
+        Ok(sumfile.flush()?)
+    }
+
+    /// Download the archive for `lang` and verify its checksum.
+    /// If `keep_path` is provided, the verified archive is also saved there.
+    ///
+    /// Returns `None` if the archive has not been modified since it was downloaded with `etag`.
+    fn download_lang_archive(
+        agent: &Agent,
+        mirror: &str,
+        lang: &str,
+        sum: &str,
+        etag: Option<&str>,
+        keep_path: Option<&Path>,
+        downloaded_bytes: &mut u64,
+    ) -> Result<Option<(PagesArchive, Option<String>)>> {
+        let Some((archive, new_etag)) =
+            Self::get_asset_if_modified(agent, &format!("{mirror}/tldr-pages.{lang}.zip"), etag)?
+        else {
+            return Ok(None);
+        };
+        *downloaded_bytes += archive.len() as u64;
+
+        info_start!("validating sha256sums... ");
+        let actual_sum = util::sha256_hexdigest(&archive);
+
+        if sum != actual_sum {
+            info_end!("{}", "FAILED".red().bold());
+            return Err(Error::new(format!(
+                "SHA256 sum mismatch!\n\
+                expected : {sum}\n\
+                got      : {actual_sum}"
+            )));
+        }
+
+        info_end!(" {}", "OK".green().bold());
+
+        if let Some(keep_path) = keep_path {
+            let parent = keep_path.parent().unwrap();
+            fs::create_dir_all(parent).path_context(parent)?;
+            fs::write(keep_path, &archive).path_context(keep_path)?;
+        }
+
+        let archive = ZipArchive::new(Cursor::new(archive))
+            .map_err(|e| Error::extract_archive(&format!("pages.{lang}"), e))?;
+
+        Ok(Some((archive, new_etag)))
+    }
+
+    /// Get the path to the kept archive of `lang`.
+    fn kept_archive_path(&self, lang: &str) -> PathBuf {
+        self.dir
+            .join(ARCHIVES_DIR)
+            .join(format!("tldr-pages.{lang}.zip"))
+    }
+
+    /// Read a previously kept archive, if it exists and matches `sum`.
+    fn read_kept_archive(path: &Path, sum: &str) -> Option<PagesArchive> {
+        let archive = fs::read(path).ok()?;
+
+        if util::sha256_hexdigest(&archive) == sum {
+            ZipArchive::new(Cursor::new(archive)).ok()
+        } else {
+            None
+        }
     }
 
     fn parse_sumfile(s: &str) -> Result<HashMap<&str, &str>> {
@@ -165,21 +515,41 @@ impl<'a> Cache<'a> {
         Ok(map)
     }
 
-    /// Extract pages from the language archive and update the page counters.
+    /// Parse the file with `ETag`s of previously downloaded archives.
+    ///
+    /// Every line contains a language code and the `ETag` of its archive, separated by a space.
+    /// Malformed lines are skipped - the worst case is an unnecessary download.
+    fn parse_etags(s: &str) -> BTreeMap<&str, String> {
+        s.lines()
+            .filter_map(|l| l.split_once(' '))
+            .map(|(lang, etag)| (lang, etag.to_string()))
+            .collect()
+    }
+
+    /// Extract pages from the language archive.
+    ///
+    /// Pages that are identical to the ones on disk are not rewritten, and pages
+    /// that are not in the archive are removed.
+    ///
+    /// Returns the number of extracted pages, the number of new pages and the number of unchanged pages.
+    /// Paths of pages that did not exist before are added to `new_pages`.
     fn extract_lang_archive(
         &self,
         lang_dir: &str,
         archive: &mut PagesArchive,
         n_existing: i32,
-        all_downloaded: &mut i32,
-        all_new: &mut i32,
-    ) -> Result<()> {
+        new_pages: &mut Vec<String>,
+    ) -> Result<PageCounts> {
         info_start!("extracting '{lang_dir}'... ");
 
         let mut n_downloaded = 0;
+        let mut n_unchanged = 0;
+        let mut extracted = HashSet::new();
 
         for i in 0..archive.len() {
-            let mut zipfile = archive.by_index(i)?;
+            let mut zipfile = archive
+                .by_index(i)
+                .map_err(|e| Error::extract_archive(lang_dir, e))?;
             let Some(fname) = zipfile.enclosed_name() else {
                 warnln!(
                     "found an unsafe path in the zip archive: '{}', ignoring it",
@@ -196,90 +566,386 @@ impl<'a> Cache<'a> {
             let path = self.dir.join(lang_dir).join(&fname);
 
             if zipfile.is_dir() {
-                fs::create_dir_all(&path)?;
+                fs::create_dir_all(&path).path_context(&path)?;
                 continue;
             }
 
-            let mut file = File::create(&path)?;
-            io::copy(&mut zipfile, &mut file)?;
+            // Read the whole page first to tell decompression errors apart from write errors.
+            let mut page = vec![];
+            zipfile
+                .read_to_end(&mut page)
+                .map_err(|e| Error::extract_archive(lang_dir, e))?;
+
+            if fs::read(&path).is_ok_and(|old| old == page) {
+                n_unchanged += 1;
+            } else {
+                if !path.exists() {
+                    new_pages.push(format!("{lang_dir}/{}", fname.display()));
+                }
+                let parent = path.parent().unwrap();
+                fs::create_dir_all(parent).path_context(parent)?;
+                fs::write(&path, page).path_context(&path)?;
+            }
 
+            extracted.insert(path);
             n_downloaded += 1;
         }
 
-        let n_new = n_downloaded - n_existing;
-        *all_downloaded += n_downloaded;
-        *all_new += n_new;
+        Self::remove_stale(&self.dir.join(lang_dir), &extracted)?;
+
+        info_end!("{}", "OK".green().bold());
+
+        Ok((n_downloaded, n_downloaded - n_existing, n_unchanged))
+    }
+
+    /// Recursively remove files in `dir` that are not in `keep`, and directories that end up empty.
+    fn remove_stale(dir: &Path, keep: &HashSet<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir).path_context(dir)? {
+            let path = entry.path_context(dir)?.path();
+
+            if path.is_dir() {
+                Self::remove_stale(&path, keep)?;
+                if fs::read_dir(&path).path_context(&path)?.next().is_none() {
+                    fs::remove_dir(&path).path_context(&path)?;
+                }
+            } else if !keep.contains(&path) {
+                fs::remove_file(&path).path_context(&path)?;
+            }
+        }
+
+        Ok(())
+    }
 
-        info_end!(
-            "{} pages, {} new",
-            n_downloaded.green().bold(),
-            n_new.green().bold()
+    /// Update a language directory with the contents of the archive.
+    ///
+    /// Returns the number of extracted, new and unchanged pages.
+    fn extract_archive(
+        &self,
+        lang_dir: &str,
+        mut archive: PagesArchive,
+        new_pages: &mut Vec<String>,
+    ) -> Result<PageCounts> {
+        // `list_all_vec` can fail when `pages.en` is empty, hence the default of 0.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let n_existing = self.list_all_vec(lang_dir).map_or(0, |v| v.len()) as i32;
+
+        let dir = self.dir.join(lang_dir);
+        fs::create_dir_all(&dir).path_context(&dir)?;
+
+        let counts = match self.extract_lang_archive(lang_dir, &mut archive, n_existing, new_pages)
+        {
+            Ok(counts) => counts,
+            Err(e) => {
+                info_end!("{}", "FAILED".red().bold());
+                return Err(e);
+            }
+        };
+
+        if lang_dir == ENGLISH_DIR {
+            self.write_platform_index()?;
+        }
+
+        Ok(counts)
+    }
+
+    /// Write the pages added in this update to the list of new pages.
+    fn write_new_pages(&self, report: &BTreeMap<String, LangReport>) -> Result<()> {
+        let path = self.dir.join(NEW_PAGES_FILE);
+        let mut file = BufWriter::new(File::create(&path).path_context(&path)?);
+
+        for page in report.values().flat_map(|r| &r.new_pages) {
+            writeln!(file, "{page}")?;
+        }
+
+        Ok(file.flush()?)
+    }
+
+    /// Print a table with the results of updating every language.
+    fn print_update_report(report: &BTreeMap<String, LangReport>) -> Result<()> {
+        if crate::QUIET.load(Relaxed) || report.is_empty() {
+            return Ok(());
+        }
+
+        let mut stderr = io::stderr().lock();
+        // Align the page count column.
+        let width = report
+            .values()
+            .filter_map(|r| match r.status {
+                LangStatus::Updated(n, ..) => Some(n.to_string().len()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        infoln!("summary:");
+
+        for (lang, r) in report {
+            let status = match r.status {
+                LangStatus::UpToDate => "up to date".green().bold().to_string(),
+                LangStatus::Updated(n, n_new, n_unchanged) => {
+                    format!(
+                        "{:width$} pages, {} new, {} unchanged",
+                        n.green().bold(),
+                        n_new.green().bold(),
+                        n_unchanged.green().bold()
+                    )
+                }
+                LangStatus::Failed => "FAILED".red().bold().to_string(),
+            };
+
+            // Language codes are at most 5 characters (ll_CC).
+            let row = if r.downloaded_bytes == 0 {
+                format!("{lang:5} : {status}")
+            } else {
+                format!(
+                    "{lang:5} : {status} ({} downloaded)",
+                    util::size_fmt(r.downloaded_bytes).green().bold()
+                )
+            };
+
+            if crate::JSON_LOGS.load(Relaxed) {
+                // Every row is a separate message.
+                infoln!("{row}");
+            } else {
+                writeln!(stderr, "{row}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Install pages from a local archive (`tldr-pages.LANGUAGE.zip`) without downloading anything.
+    pub fn import(&self, path: &Path) -> Result<()> {
+        let fname = path
+            .file_name()
+            .map(|x| x.to_string_lossy())
+            .unwrap_or_default();
+        let Some(lang) = fname
+            .strip_prefix("tldr-pages.")
+            .and_then(|x| x.strip_suffix(".zip"))
+            .filter(|x| !x.is_empty() && !x.contains('.'))
+        else {
+            return Err(Error::new(format!(
+                "could not determine the language of '{}'.\n\
+                The archive must be named 'tldr-pages.LANGUAGE.zip', like the ones from the mirror.",
+                path.display()
+            )));
+        };
+
+        let bytes = fs::read(path).path_context(path)?;
+        let archive = ZipArchive::new(Cursor::new(bytes))?;
+
+        fs::create_dir_all(self.dir).path_context(self.dir)?;
+        // Only updates are tracked in the list of new pages.
+        let (n, n_new, n_unchanged) =
+            self.extract_archive(&format!("pages.{lang}"), archive, &mut vec![])?;
+
+        infoln!(
+            "import successful (total: {} pages, {} new, {} unchanged).",
+            n.green().bold(),
+            n_new.green().bold(),
+            n_unchanged.green().bold(),
         );
 
         Ok(())
     }
 
-    /// Delete the old cache and replace it with a fresh copy.
-    pub fn update(&self, mirror: &str, languages: &mut Vec<String>) -> Result<()> {
+    /// Update all languages from the config.
+    ///
+    /// This also records the time of the update, which is used to schedule automatic updates.
+    pub fn update(&self, cfg: &Config) -> Result<()> {
+        self.run_update(cfg, &cfg.cache.languages, true)
+    }
+
+    /// Update only `languages`. Other installed languages are left as they are.
+    ///
+    /// The time of the last update is not changed, because the other languages were not checked.
+    pub fn update_languages(&self, cfg: &Config, languages: &[String]) -> Result<()> {
+        self.run_update(cfg, languages, false)
+    }
+
+    /// Update `languages`. If `record_time` is `true`, the time of the update is saved.
+    fn run_update(&self, cfg: &Config, languages: &[String], record_time: bool) -> Result<()> {
+        // Caches created by older versions use the age of the sumfile, which is about to be
+        // replaced. Keep their age when only some languages are updated.
+        let previous_update = (!record_time && !self.dir.join(LAST_UPDATE_FILE).is_file())
+            .then(|| self.age().ok())
+            .flatten()
+            .and_then(|age| SystemTime::now().checked_sub(age));
+        let mirrors = cfg.mirrors();
+        let mut languages = languages.to_vec();
         // Sort to always download archives in alphabetical order.
         languages.sort_unstable();
         // The user can put duplicates in the config file.
         languages.dedup();
 
-        let archives = self.download_and_verify(mirror, languages)?;
+        let mut counts = BTreeMap::new();
+        // Languages that still need to be downloaded.
+        let mut failed = languages;
+        let mut sums_downloaded = false;
+        let mut report = BTreeMap::new();
+        let agent = Self::agent(cfg);
+        // The cache directory does not exist before the first update.
+        let size_before = Self::dir_size(self.dir).unwrap_or(0);
+
+        for (i, mirror) in mirrors.iter().enumerate() {
+            if mirrors.len() > 1 {
+                infoln!("using mirror '{mirror}'");
+            }
+
+            match self.download_and_verify(
+                &agent,
+                mirror,
+                &failed,
+                cfg.cache.keep_archives,
+                cfg.download_delay(),
+                &mut report,
+            ) {
+                Ok((mirror_counts, mirror_failed)) => {
+                    sums_downloaded = true;
+                    counts.extend(mirror_counts);
+                    failed = mirror_failed;
+
+                    if failed.is_empty() {
+                        break;
+                    }
+                }
+                // Try the next mirror if there is one.
+                Err(e) if matches!(e.kind, ErrorKind::Download) && i + 1 != mirrors.len() => {
+                    warnln!("{e}");
+                }
+                // If some languages were already downloaded, report only the failed languages.
+                Err(e) if matches!(e.kind, ErrorKind::Download) && sums_downloaded => {
+                    warnln!("{e}");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Failed languages keep their old sums (or none) in the sumfile, so they are retried
+        // on the next update. Updating only some languages does not postpone the next
+        // automatic update, because the other ones were not checked.
+        if record_time && sums_downloaded {
+            self.write_last_update(SystemTime::now())?;
+        } else if let Some(time) = previous_update.filter(|_| sums_downloaded) {
+            self.write_last_update(time)?;
+        }
+
+        // Keep the list from the previous update if no pages were changed.
+        if !counts.is_empty() {
+            self.write_new_pages(&report)?;
+        }
 
-        if archives.is_empty() {
+        if counts.is_empty() && failed.is_empty() {
             infoln!(
                 "there is nothing to do. Run 'tldr --clean-cache' if you want to force an update."
             );
             return Ok(());
         }
 
+        let size_after = Self::dir_size(self.dir).unwrap_or(0);
+
         let mut all_downloaded = 0;
         let mut all_new = 0;
+        let mut all_unchanged = 0;
+        for (n, n_new, n_unchanged) in counts.into_values() {
+            all_downloaded += n;
+            all_new += n_new;
+            all_unchanged += n_unchanged;
+        }
+        let downloaded_bytes = report.values().map(|r| r.downloaded_bytes).sum();
 
-        for (lang_dir, mut archive) in archives {
-            // `list_all_vec` can fail when `pages.en` is empty, hence the default of 0.
-            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-            let n_existing = self.list_all_vec(&lang_dir).map(|v| v.len()).unwrap_or(0) as i32;
+        Self::print_update_report(&report)?;
 
-            let lang_dir_full = self.dir.join(&lang_dir);
-            if lang_dir_full.is_dir() {
-                fs::remove_dir_all(&lang_dir_full)?;
-            }
+        let (change, delta) = if size_after >= size_before {
+            ("grew", size_after - size_before)
+        } else {
+            ("shrank", size_before - size_after)
+        };
+        infoln!(
+            "downloaded {}, cache {change} by {}.",
+            util::size_fmt(downloaded_bytes).green().bold(),
+            util::size_fmt(delta).green().bold(),
+        );
 
-            if let Err(e) = self.extract_lang_archive(
-                &lang_dir,
-                &mut archive,
-                n_existing,
-                &mut all_downloaded,
-                &mut all_new,
-            ) {
-                info_end!("{}", "FAILED".red().bold());
-                return Err(e);
-            }
+        if failed.is_empty() {
+            infoln!(
+                "cache update successful (total: {} pages, {} new, {} unchanged).",
+                all_downloaded.green().bold(),
+                all_new.green().bold(),
+                all_unchanged.green().bold(),
+            );
+
+            return Ok(());
         }
 
-        infoln!(
-            "cache update successful (total: {} pages, {} new).",
-            all_downloaded.green().bold(),
-            all_new.green().bold(),
-        );
+        if all_downloaded != 0 {
+            infoln!(
+                "cache partially updated (total: {} pages, {} new, {} unchanged).",
+                all_downloaded.green().bold(),
+                all_new.green().bold(),
+                all_unchanged.green().bold(),
+            );
+        }
 
-        Ok(())
+        Err(Error::partial_update(&failed))
     }
 
     /// Delete the cache directory.
     pub fn clean(&self) -> Result<()> {
         if !self.dir.is_dir() {
             infoln!("cache does not exist, not cleaning.");
-            fs::create_dir_all(self.dir)?;
+            fs::create_dir_all(self.dir).path_context(self.dir)?;
             return Ok(());
         }
 
         infoln!("cleaning the cache directory...");
-        fs::remove_dir_all(self.dir)?;
-        fs::create_dir_all(self.dir)?;
+        fs::remove_dir_all(self.dir).path_context(self.dir)?;
+        fs::create_dir_all(self.dir).path_context(self.dir)?;
+
+        Ok(())
+    }
+
+    /// Delete language directories of languages that are not in `languages`.
+    ///
+    /// English pages are always kept. If `confirm` is `true`, ask the user before deleting anything.
+    pub fn prune(&self, languages: &[String], confirm: bool) -> Result<()> {
+        let to_remove: Vec<String> = self
+            .get_lang_dirs()?
+            .into_iter()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .filter(|dir| {
+                dir.strip_prefix("pages.")
+                    .is_some_and(|lang| lang != "en" && !languages.iter().any(|x| x == lang))
+            })
+            .collect();
+
+        if to_remove.is_empty() {
+            infoln!("there are no languages to remove.");
+            return Ok(());
+        }
+
+        if confirm
+            && !util::confirm(&format!(
+                "The following directories will be removed: {}. Continue?",
+                to_remove.join(", ").bold()
+            ))?
+        {
+            infoln!("aborting.");
+            return Ok(());
+        }
+
+        for lang_dir in to_remove {
+            infoln!("removing '{lang_dir}'...");
+            let path = self.dir.join(&lang_dir);
+            fs::remove_dir_all(&path).path_context(&path)?;
+
+            // This is safe to unwrap, only directories starting with "pages." are removed.
+            let kept_archive = self.kept_archive_path(lang_dir.strip_prefix("pages.").unwrap());
+            if kept_archive.is_file() {
+                fs::remove_file(&kept_archive).path_context(&kept_archive)?;
+            }
+        }
 
         Ok(())
     }
@@ -287,29 +953,68 @@ impl<'a> Cache<'a> {
     /// Find out what platforms are available.
     fn get_platforms(&self) -> Result<&[OsString]> {
         self.platforms
-            .get_or_try_init(|| {
-                let mut result = vec![];
+            .get_or_try_init(|| match self.read_platform_index() {
+                Some(platforms) => Ok(platforms),
+                None => self.scan_platforms(),
+            })
+            .map(Vec::as_slice)
+    }
 
-                for entry in fs::read_dir(self.dir.join(ENGLISH_DIR))? {
-                    let entry = entry?;
-                    let path = entry.path();
-                    let platform = path.file_name().unwrap();
+    /// Read the platforms from the directory names in `pages.en` (sorted alphabetically).
+    fn scan_platforms(&self) -> Result<Vec<OsString>> {
+        let mut result = vec![];
 
-                    result.push(platform.to_os_string());
-                }
+        for entry in fs::read_dir(self.dir.join(ENGLISH_DIR))? {
+            let entry = entry?;
+            let path = entry.path();
+            let platform = path.file_name().unwrap();
 
-                if result.is_empty() {
-                    Err(Error::messed_up_cache(
-                        "'pages.en' contains no platform directories.",
-                    ))
-                } else {
-                    // read_dir() order can differ across runs, so it's
-                    // better to sort the Vec for consistency.
-                    result.sort_unstable();
-                    Ok(result)
-                }
-            })
-            .map(Vec::as_slice)
+            result.push(platform.to_os_string());
+        }
+
+        if result.is_empty() {
+            Err(Error::messed_up_cache(
+                "'pages.en' contains no platform directories.",
+            ))
+        } else {
+            // read_dir() order can differ across runs, so it's
+            // better to sort the Vec for consistency.
+            result.sort_unstable();
+            Ok(result)
+        }
+    }
+
+    /// Read the platforms from the platform index written on update.
+    ///
+    /// `None` is returned if the index does not exist or is older than `pages.en`.
+    fn read_platform_index(&self) -> Option<Vec<OsString>> {
+        let index_path = self.dir.join(PLATFORM_INDEX_FILE);
+        let mtime = |path: &Path| path.metadata().and_then(|m| m.modified()).ok();
+
+        // Adding or removing a platform directory changes the modification time of `pages.en`.
+        if mtime(&self.dir.join(ENGLISH_DIR))? > mtime(&index_path)? {
+            return None;
+        }
+
+        let platforms: Vec<OsString> = fs::read_to_string(index_path)
+            .ok()?
+            .lines()
+            .filter(|x| !x.is_empty())
+            .map(OsString::from)
+            .collect();
+
+        (!platforms.is_empty()).then_some(platforms)
+    }
+
+    /// Write the platforms in `pages.en` to the platform index.
+    fn write_platform_index(&self) -> Result<()> {
+        let path = self.dir.join(PLATFORM_INDEX_FILE);
+        let mut index = BufWriter::new(File::create(&path).path_context(&path)?);
+        for platform in self.scan_platforms()? {
+            writeln!(index, "{}", platform.to_string_lossy())?;
+        }
+
+        Ok(index.flush()?)
     }
 
     /// Find out what platforms are available and check if the provided platform exists.
@@ -317,11 +1022,24 @@ impl<'a> Cache<'a> {
         let platforms = self.get_platforms()?;
 
         if platforms.iter().all(|x| x != platform) {
-            Err(Error::new(format!(
+            let mut message = format!(
                 "platform '{platform}' does not exist.\n{} {}.",
                 "Possible values:".bold(),
                 platforms.join(", ".as_ref()).to_string_lossy()
-            )))
+            );
+
+            // Suggest a platform only if the name is likely a typo.
+            let closest = platforms
+                .iter()
+                .map(|x| x.to_string_lossy())
+                .map(|x| (util::edit_distance(platform, &x), x))
+                .min_by_key(|(dist, _)| *dist)
+                .filter(|(dist, _)| *dist <= 2 && *dist < platform.len());
+            if let Some((_, suggestion)) = closest {
+                let _ = write!(message, "\nDid you mean '{}'?", suggestion.bold());
+            }
+
+            Err(Error::new(message))
         } else {
             Ok(platforms)
         }
@@ -343,35 +1061,65 @@ impl<'a> Cache<'a> {
         None
     }
 
+    /// Get the directory of `lang`.
+    ///
+    /// Archives are published per language, so `ll_CC` falls back to `ll`
+    /// if there is no `pages.ll_CC` directory (e.g. `de_DE` => `pages.de`).
+    fn lang_dir(&self, lang: &str) -> String {
+        let lang_dir = format!("pages.{lang}");
+
+        match lang.split_once('_') {
+            Some((base, _)) if !self.subdir_exists(&lang_dir) => format!("pages.{base}"),
+            _ => lang_dir,
+        }
+    }
+
     /// Find all pages with the given name.
-    pub fn find(&self, name: &str, languages: &[String], platform: &str) -> Result<Vec<PathBuf>> {
+    ///
+    /// `platforms` are searched in order. If `include_common` is `false`, `common` is only
+    /// searched after all other platforms (unless it is one of the requested platforms).
+    pub fn find(
+        &self,
+        name: &str,
+        languages: &[String],
+        platforms: &[&str],
+        include_common: bool,
+    ) -> Result<Vec<PathBuf>> {
         // https://github.com/tldr-pages/tldr/blob/main/CLIENT-SPECIFICATION.md#page-resolution
 
-        let platforms = self.get_platforms_and_check(platform)?;
+        let all_platforms = self.get_platforms()?;
+        for platform in platforms {
+            self.get_platforms_and_check(platform)?;
+        }
         let file = format!("{name}.md");
+        let search_common = include_common || platforms.contains(&"common");
+        let platform = platforms.join(", ");
 
         let mut result = vec![];
-        let mut lang_dirs: Vec<String> = languages.iter().map(|x| format!("pages.{x}")).collect();
+        let mut lang_dirs: Vec<String> = languages.iter().map(|x| self.lang_dir(x)).collect();
         // We can't sort here - order is defined by the user.
         lang_dirs.dedup_nosort();
 
-        // `common` is always searched, so we skip the search for the specified platform
-        // if the user has requested only `common` (to prevent searching twice)
-        if platform != "common" {
+        // `common` is searched below, so we skip it here (to prevent searching twice).
+        for platform in platforms.iter().filter(|&&x| x != "common") {
             if let Some(path) = self.find_page_for(&file, platform, &lang_dirs) {
                 result.push(path);
             }
         }
 
         // Fall back to `common` if the page is not found in `platform`.
-        if let Some(path) = self.find_page_for(&file, "common", &lang_dirs) {
-            result.push(path);
+        if search_common {
+            if let Some(path) = self.find_page_for(&file, "common", &lang_dirs) {
+                result.push(path);
+            }
         }
 
-        // Fall back to all other platforms if the page is not found in `platform`.
-        for alt_platform in platforms {
-            // `platform` and `common` were already searched, so we can skip them here.
-            if alt_platform == platform || alt_platform == "common" {
+        // Fall back to all other platforms if the page is not found in `platforms`.
+        for alt_platform in all_platforms {
+            // `platforms` and `common` were already searched, so we can skip them here.
+            if platforms.iter().any(|x| alt_platform == *x)
+                || (search_common && alt_platform == "common")
+            {
                 continue;
             }
 
@@ -379,15 +1127,15 @@ impl<'a> Cache<'a> {
                 if result.is_empty() {
                     let alt_platform = alt_platform.to_string_lossy();
 
-                    if platform == "common" {
+                    if search_common && !platforms.contains(&"common") {
                         warnln!(
                             "showing page from platform '{alt_platform}', \
-                            because '{name}' does not exist in 'common'"
+                            because '{name}' does not exist in '{platform}' and 'common'"
                         );
                     } else {
                         warnln!(
                             "showing page from platform '{alt_platform}', \
-                            because '{name}' does not exist in '{platform}' and 'common'"
+                            because '{name}' does not exist in '{platform}'"
                         );
                     }
                 }
@@ -399,6 +1147,69 @@ impl<'a> Cache<'a> {
         Ok(result)
     }
 
+    /// Download a single page from `cache.on_demand_mirror` and save it in the cache.
+    ///
+    /// Languages are tried in order, first for `platforms` and then for `common`
+    /// (if `include_common` is `true`). `None` is returned if the page does not exist.
+    pub fn fetch_page(
+        &self,
+        cfg: &Config,
+        name: &str,
+        languages: &[String],
+        platforms: &[&str],
+        include_common: bool,
+    ) -> Result<Option<PathBuf>> {
+        // These are used in both the URL and the path of the downloaded page,
+        // they must not point outside of the directory.
+        let is_unsafe = |s: &str| s.contains(['/', '\\']) || s.contains("..");
+        if is_unsafe(name) {
+            return Err(Error::new(format!("invalid page name: '{name}'.")));
+        }
+        if let Some(x) = platforms.iter().copied().find(|x| is_unsafe(x)) {
+            return Err(Error::new(format!("invalid platform: '{x}'.")));
+        }
+        if let Some(x) = languages.iter().find(|x| is_unsafe(x)) {
+            return Err(Error::new(format!("invalid language: '{x}'.")));
+        }
+
+        let agent = Self::agent(cfg);
+        let mirror = cfg.cache.on_demand_mirror.trim_end_matches('/');
+
+        let mut platforms = platforms.to_vec();
+        if include_common && !platforms.contains(&"common") {
+            platforms.push("common");
+        }
+
+        for lang in languages {
+            for platform in &platforms {
+                // English pages are in 'pages', not 'pages.en' in the tldr repository.
+                let repo_dir = if lang == "en" {
+                    "pages".to_string()
+                } else {
+                    format!("pages.{lang}")
+                };
+                let url = format!("{mirror}/{repo_dir}/{platform}/{name}.md");
+                debugln!("GET {url}");
+
+                let page = match agent.inner.get(&url).call() {
+                    Ok(mut resp) => Self::read_body(&agent, &url, resp.body_mut())?,
+                    Err(ureq::Error::StatusCode(404)) => continue,
+                    Err(e) => return Err(e.into()),
+                };
+
+                let dir = self.dir.join(format!("pages.{lang}")).join(platform);
+                fs::create_dir_all(&dir).path_context(&dir)?;
+                let path = dir.join(format!("{name}.md"));
+                fs::write(&path, page).path_context(&path)?;
+
+                infoln!("downloaded page '{name}' ({lang}, {platform}) on demand");
+                return Ok(Some(path));
+            }
+        }
+
+        Ok(None)
+    }
+
     /// List all available pages in `lang` for `platform`.
     fn list_dir<P, Q>(&self, platform: P, lang_dir: Q) -> Result<Vec<OsString>>
     where
@@ -417,13 +1228,18 @@ impl<'a> Cache<'a> {
         }
     }
 
-    fn print_basenames(mut pages: Vec<OsString>) -> Result<()> {
+    fn print_basenames(pages: Vec<OsString>) -> Result<()> {
         if pages.is_empty() {
             return Err(Error::messed_up_cache(
                 "no pages found, but the 'pages.en' directory exists.",
             ));
         }
 
+        Self::write_basenames(pages)
+    }
+
+    /// Print page names without the `.md` extension. Unlike `print_basenames`, this allows `pages` to be empty.
+    fn write_basenames(mut pages: Vec<OsString>) -> Result<()> {
         // Show pages in alphabetical order.
         pages.sort_unstable();
         // There are pages with the same name across multiple platforms.
@@ -442,21 +1258,207 @@ impl<'a> Cache<'a> {
         Ok(stdout.flush()?)
     }
 
-    /// List all pages in English for `platform` and common.
-    pub fn list_for(&self, platform: &str) -> Result<()> {
-        // This is here just to check if the platform exists.
-        self.get_platforms_and_check(platform)?;
+    /// Get the directory of `language` (English if it is `None`).
+    /// If the language is not installed, English is used instead.
+    fn list_lang_dir(&self, language: Option<&str>) -> Result<String> {
+        let Some(lang) = language else {
+            return Ok(ENGLISH_DIR.to_string());
+        };
+
+        let lang_dir = format!("pages.{lang}");
+        if self.subdir_exists(&lang_dir) {
+            Ok(lang_dir)
+        } else {
+            warnln!("language '{lang}' is not installed, listing English pages instead");
+            Ok(ENGLISH_DIR.to_string())
+        }
+    }
 
-        let pages = if platform == "common" {
-            self.list_dir(platform, ENGLISH_DIR)?
+    /// Print pages from `lang_dir`. Only English pages are expected to never be empty.
+    fn print_lang_basenames(lang_dir: &str, pages: Vec<OsString>) -> Result<()> {
+        if lang_dir == ENGLISH_DIR {
+            Self::print_basenames(pages)
         } else {
-            self.list_dir(platform, ENGLISH_DIR)?
-                .into_iter()
-                .chain(self.list_dir("common", ENGLISH_DIR)?)
-                .collect()
+            Self::write_basenames(pages)
+        }
+    }
+
+    /// List all pages in `lang_dir` for `platforms` and common (if `include_common` is `true`)
+    /// and return a `Vec`.
+    fn list_for_vec(
+        &self,
+        platforms: &[&str],
+        include_common: bool,
+        lang_dir: &str,
+    ) -> Result<Vec<OsString>> {
+        let mut pages = vec![];
+
+        for platform in platforms {
+            // This is here just to check if the platform exists.
+            self.get_platforms_and_check(platform)?;
+            pages.append(&mut self.list_dir(platform, lang_dir)?);
+        }
+
+        if include_common && !platforms.contains(&"common") {
+            pages.append(&mut self.list_dir("common", lang_dir)?);
+        }
+
+        Ok(pages)
+    }
+
+    /// List all pages in `language` (English if it is `None`) for `platforms`
+    /// and common (if `include_common` is `true`).
+    pub fn list_for(
+        &self,
+        platforms: &[&str],
+        include_common: bool,
+        language: Option<&str>,
+    ) -> Result<()> {
+        let lang_dir = self.list_lang_dir(language)?;
+        let pages = self.list_for_vec(platforms, include_common, &lang_dir)?;
+        Self::print_lang_basenames(&lang_dir, pages)
+    }
+
+    /// Print the number of pages in `language` (English if it is `None`) for `platforms`
+    /// and common (if `include_common` is `true`).
+    pub fn count_for(
+        &self,
+        platforms: &[&str],
+        include_common: bool,
+        language: Option<&str>,
+    ) -> Result<()> {
+        let lang_dir = self.list_lang_dir(language)?;
+        let mut pages = self.list_for_vec(platforms, include_common, &lang_dir)?;
+        // Pages with the same name in multiple platforms are counted once, like in `list_for`.
+        pages.sort_unstable();
+        pages.dedup();
+
+        writeln!(io::stdout(), "{}", pages.len())?;
+        Ok(())
+    }
+
+    /// Print the number of platform and language variants of a page.
+    /// All installed languages are searched if `languages` is `None`.
+    pub fn count_variants(&self, name: &str, languages: Option<&[String]>) -> Result<()> {
+        let mut lang_dirs = match languages {
+            Some(languages) => languages
+                .iter()
+                .map(|x| OsString::from(format!("pages.{x}")))
+                .collect(),
+            None => self.get_lang_dirs()?,
+        };
+        lang_dirs.sort_unstable();
+        lang_dirs.dedup();
+
+        let file = format!("{name}.md");
+        let mut count = 0;
+        for lang_dir in &lang_dirs {
+            for platform in self.get_platforms()? {
+                if self.dir.join(lang_dir).join(platform).join(&file).is_file() {
+                    count += 1;
+                }
+            }
+        }
+
+        writeln!(io::stdout(), "{count}")?;
+        Ok(())
+    }
+
+    /// List pages in `language` (English if it is `None`) for `platforms` and common
+    /// (if `include_common` is `true`) that were added in the last update (for `--list --new`).
+    /// With `--platform all`, new pages from every platform are listed.
+    pub fn list_new(
+        &self,
+        platforms: &[&str],
+        include_common: bool,
+        language: Option<&str>,
+    ) -> Result<()> {
+        let new_pages = match fs::read_to_string(self.dir.join(NEW_PAGES_FILE)) {
+            Ok(s) => s,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                infoln!(
+                    "new pages are not tracked yet, they will be listed after the next update."
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
         };
 
-        Self::print_basenames(pages)
+        let all_platforms = platforms.contains(&"all");
+        if !all_platforms {
+            for platform in platforms {
+                self.get_platforms_and_check(platform)?;
+            }
+        }
+        let lang = self.list_lang_dir(language)?;
+        let lang = lang.strip_prefix("pages.").unwrap_or(&lang);
+
+        let pages = new_pages
+            .lines()
+            .map(Path::new)
+            .filter(|path| path.page_language().is_some_and(|x| x == lang))
+            .filter(|path| {
+                path.page_platform().is_some_and(|p| {
+                    all_platforms || platforms.contains(&&*p) || (include_common && p == "common")
+                })
+            })
+            .filter_map(|path| path.file_name().map(OsStr::to_os_string))
+            .collect();
+
+        // There might be no new pages in this language or platform.
+        Self::write_basenames(pages)
+    }
+
+    /// List all pages in English grouped by platform (for `--list --platform all`).
+    pub fn list_by_platform(&self) -> Result<()> {
+        let mut stdout = BufWriter::new(io::stdout().lock());
+
+        for (i, platform) in self.get_platforms()?.iter().enumerate() {
+            let mut pages = self.list_dir(platform, ENGLISH_DIR)?;
+            pages.sort_unstable();
+
+            if i != 0 {
+                writeln!(stdout)?;
+            }
+            writeln!(
+                stdout,
+                "{}",
+                format!("{}:", platform.to_string_lossy()).bold()
+            )?;
+
+            for page in pages {
+                let page = page.to_string_lossy();
+                let page = page.strip_suffix(".md").unwrap_or(&page);
+                writeln!(stdout, "  {page}")?;
+            }
+        }
+
+        Ok(stdout.flush()?)
+    }
+
+    /// List pages in `languages` for `platforms` only (without `common`).
+    pub fn list_for_languages(&self, platforms: &[&str], languages: &[String]) -> Result<()> {
+        for platform in platforms {
+            self.get_platforms_and_check(platform)?;
+        }
+
+        let mut pages = vec![];
+
+        for lang in languages {
+            let lang_dir = format!("pages.{lang}");
+
+            if !self.subdir_exists(&lang_dir) {
+                return Err(Error::new(format!("language '{lang}' is not installed."))
+                    .describe(Error::DESC_LANG_NOT_INSTALLED));
+            }
+
+            for platform in platforms {
+                pages.append(&mut self.list_dir(platform, &lang_dir)?);
+            }
+        }
+
+        // Some platform directories do not exist in some translations, so `pages` can be empty.
+        Self::write_basenames(pages)
     }
 
     /// List all pages in `lang` and return a `Vec`.
@@ -473,9 +1475,10 @@ impl<'a> Cache<'a> {
         Ok(result)
     }
 
-    /// List all pages in English.
-    pub fn list_all(&self) -> Result<()> {
-        Self::print_basenames(self.list_all_vec(ENGLISH_DIR)?)
+    /// List all pages in `language` (English if it is `None`).
+    pub fn list_all(&self, language: Option<&str>) -> Result<()> {
+        let lang_dir = self.list_lang_dir(language)?;
+        Self::print_lang_basenames(&lang_dir, self.list_all_vec(&lang_dir)?)
     }
 
     /// List platforms (used in shell completions).
@@ -485,11 +1488,22 @@ impl<'a> Cache<'a> {
         Ok(())
     }
 
+    /// Find out what language directories are installed (sorted alphabetically).
+    fn get_lang_dirs(&self) -> Result<Vec<OsString>> {
+        let mut lang_dirs = fs::read_dir(self.dir)?
+            .filter(|res| res.is_ok() && res.as_ref().unwrap().path().is_dir())
+            .map(|res| res.unwrap().file_name())
+            // Skip other directories (e.g. kept archives).
+            .filter(|dir| dir.to_string_lossy().starts_with("pages."))
+            .collect::<Vec<OsString>>();
+        lang_dirs.sort_unstable();
+
+        Ok(lang_dirs)
+    }
+
     /// List languages (used in shell completions).
     pub fn list_languages(&self) -> Result<()> {
-        let languages = fs::read_dir(self.dir)?
-            .filter(|res| res.is_ok() && res.as_ref().unwrap().path().is_dir())
-            .map(|res| res.unwrap().file_name());
+        let languages = self.get_lang_dirs()?;
         let mut stdout = io::stdout().lock();
 
         for lang in languages {
@@ -502,26 +1516,212 @@ impl<'a> Cache<'a> {
         Ok(())
     }
 
-    /// Show cache information.
-    pub fn info(&self, cfg: &Config) -> Result<()> {
-        let mut n_map = BTreeMap::new();
-        let mut n_total = 0;
+    /// Print installed and configured languages as TSV or JSON.
+    ///
+    /// Languages that are installed but not configured are removed by --prune-languages,
+    /// and languages that are configured but not installed are downloaded on the next update.
+    pub fn export_languages(&self, configured: &[String], format: OutputFormat) -> Result<()> {
+        // Language => (installed, configured)
+        let mut languages: BTreeMap<String, (bool, bool)> = BTreeMap::new();
+
+        for lang_dir in self.get_lang_dirs()? {
+            let lang_dir = lang_dir.to_string_lossy();
+            if let Some(lang) = lang_dir.strip_prefix("pages.") {
+                languages.entry(lang.to_string()).or_default().0 = true;
+            }
+        }
+        for lang in configured {
+            languages.entry(lang.clone()).or_default().1 = true;
+        }
+
+        let mut stdout = BufWriter::new(io::stdout().lock());
+
+        if let OutputFormat::Json = format {
+            let languages: Vec<String> = languages
+                .iter()
+                .map(|(lang, (installed, configured))| {
+                    format!(
+                        r#"{{ "language": "{}", "installed": {installed}, "configured": {configured} }}"#,
+                        util::json_escape(lang)
+                    )
+                })
+                .collect();
+
+            writeln!(stdout, r#"{{ "languages": [{}] }}"#, languages.join(", "))?;
+        } else {
+            writeln!(stdout, "language\tinstalled\tconfigured")?;
+            for (lang, (installed, configured)) in languages {
+                writeln!(stdout, "{lang}\t{installed}\t{configured}")?;
+            }
+        }
+
+        Ok(stdout.flush()?)
+    }
+
+    /// Check if all pages in `lang_dir` are readable and valid.
+    ///
+    /// Returns the number of checked pages, a list of page problems and a list of errors
+    /// encountered while reading the directories.
+    fn verify_lang_dir(&self, lang_dir: &OsStr, cfg: &Config) -> (usize, Vec<String>, Vec<String>) {
+        let mut n_pages = 0;
+        let mut problems = vec![];
+        let mut dir_errors = vec![];
+
+        let platform_dirs = match fs::read_dir(self.dir.join(lang_dir)) {
+            Ok(entries) => entries,
+            Err(e) => {
+                let dir_error = format!("'{}': {e}", lang_dir.to_string_lossy());
+                return (0, vec![], vec![dir_error]);
+            }
+        };
 
-        for lang_dir in fs::read_dir(self.dir)? {
-            let lang_dir = lang_dir?;
-            if !lang_dir.path().is_dir() {
+        for platform_dir in platform_dirs {
+            let pages = match platform_dir.and_then(|ent| fs::read_dir(ent.path())) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    dir_errors.push(format!("'{}': {e}", lang_dir.to_string_lossy()));
+                    continue;
+                }
+            };
+
+            for page in pages {
+                let path = match page {
+                    Ok(ent) => ent.path(),
+                    Err(e) => {
+                        dir_errors.push(format!("'{}': {e}", lang_dir.to_string_lossy()));
+                        continue;
+                    }
+                };
+                n_pages += 1;
+
+                match fs::metadata(&path) {
+                    Ok(m) if m.len() == 0 => {
+                        problems.push(format!("'{}': the page is empty", path.display()));
+                    }
+                    Ok(_) => {
+                        if let Err(e) = PageRenderer::validate(&path, cfg) {
+                            problems.push(e.to_string());
+                        }
+                    }
+                    Err(e) => problems.push(format!("'{}': {e}", path.display())),
+                }
+            }
+        }
+
+        (n_pages, problems, dir_errors)
+    }
+
+    /// Check if all pages in the cache are readable and valid.
+    pub fn verify(&self, cfg: &Config) -> Result<()> {
+        let lang_dirs = self.get_lang_dirs()?;
+        let mut n_failed = 0;
+        let mut n_dir_errors = 0;
+
+        for lang_dir in lang_dirs {
+            info_start!("verifying '{}'... ", lang_dir.to_string_lossy());
+            let (n_pages, problems, dir_errors) = self.verify_lang_dir(&lang_dir, cfg);
+
+            if problems.is_empty() && dir_errors.is_empty() {
+                info_end!("{} pages {}", n_pages.green().bold(), "OK".green().bold());
                 continue;
             }
-            let lang_dir = lang_dir.file_name();
+
+            if dir_errors.is_empty() {
+                info_end!(
+                    "{} of {} pages {}",
+                    problems.len().red().bold(),
+                    n_pages.green().bold(),
+                    "FAILED".red().bold()
+                );
+            } else {
+                info_end!(
+                    "{} of {} pages {}, {} directory error(s)",
+                    problems.len().red().bold(),
+                    n_pages.green().bold(),
+                    "FAILED".red().bold(),
+                    dir_errors.len().red().bold()
+                );
+            }
+            for problem in dir_errors.iter().chain(&problems) {
+                warnln!("{problem}");
+            }
+
+            n_failed += problems.len();
+            n_dir_errors += dir_errors.len();
+        }
+
+        if n_failed == 0 && n_dir_errors == 0 {
+            infoln!("all pages are valid.");
+            Ok(())
+        } else if n_dir_errors == 0 {
+            Err(Error::messed_up_cache(&format!(
+                "{n_failed} page(s) failed verification."
+            )))
+        } else {
+            Err(Error::messed_up_cache(&format!(
+                "{n_failed} page(s) failed verification, {n_dir_errors} directory error(s)."
+            )))
+        }
+    }
+
+    /// Get the number of pages and the disk usage of every installed language.
+    fn lang_stats(&self) -> Result<BTreeMap<String, (usize, u64)>> {
+        let mut n_map = BTreeMap::new();
+
+        for lang_dir in self.get_lang_dirs()? {
             let n = self.list_all_vec(&lang_dir)?.len();
+            let size = Self::dir_size(&self.dir.join(&lang_dir))?;
 
             let lang = lang_dir.to_string_lossy();
             let lang = lang.strip_prefix("pages.").unwrap_or(&lang);
 
-            n_map.insert(lang.to_string(), n);
-            n_total += n;
+            n_map.insert(lang.to_string(), (n, size));
+        }
+
+        Ok(n_map)
+    }
+
+    /// Print the number of pages and the disk usage of every installed language
+    /// in a format that is easy to parse (TSV or JSON).
+    pub fn export_stats(&self, format: OutputFormat) -> Result<()> {
+        let n_map = self.lang_stats()?;
+        let n_total: usize = n_map.values().map(|(n, _)| n).sum();
+        let size_total: u64 = n_map.values().map(|(_, size)| size).sum();
+        let mut stdout = BufWriter::new(io::stdout().lock());
+
+        if let OutputFormat::Json = format {
+            let languages: Vec<String> = n_map
+                .iter()
+                .map(|(lang, (n, size))| {
+                    format!(
+                        r#"{{ "language": "{}", "page_count": {n}, "disk_bytes": {size} }}"#,
+                        util::json_escape(lang)
+                    )
+                })
+                .collect();
+
+            writeln!(
+                stdout,
+                r#"{{ "languages": [{}], "total": {{ "page_count": {n_total}, "disk_bytes": {size_total} }} }}"#,
+                languages.join(", ")
+            )?;
+        } else {
+            writeln!(stdout, "language\tpage_count\tdisk_bytes")?;
+            for (lang, (n, size)) in n_map {
+                writeln!(stdout, "{lang}\t{n}\t{size}")?;
+            }
+            writeln!(stdout, "total\t{n_total}\t{size_total}")?;
         }
 
+        Ok(stdout.flush()?)
+    }
+
+    /// Show cache information.
+    pub fn info(&self, cfg: &Config) -> Result<()> {
+        let n_map = self.lang_stats()?;
+        let n_total: usize = n_map.values().map(|(n, _)| n).sum();
+        let size_total: u64 = n_map.values().map(|(_, size)| size).sum();
+
         let mut stdout = io::stdout().lock();
         let age = self.age()?.as_secs();
 
@@ -549,24 +1749,72 @@ impl<'a> Cache<'a> {
 
         writeln!(stdout, "Installed languages:")?;
 
-        for (lang, n) in n_map {
+        // Align the size column.
+        let width = n_total.to_string().len();
+
+        for (lang, (n, size)) in n_map {
             writeln!(
                 stdout,
                 // Language codes are at most 5 characters (ll_CC).
-                "{lang:5} : {}",
+                "{lang:5} : {:width$} ({})",
                 n.green().bold(),
+                util::size_fmt(size).green().bold(),
             )?;
         }
 
-        writeln!(stdout, "total : {} pages", n_total.green().bold())?;
+        writeln!(
+            stdout,
+            "total : {} pages ({})",
+            n_total.green().bold(),
+            util::size_fmt(size_total).green().bold()
+        )?;
 
         Ok(())
     }
 
+    /// Calculate the total size of files in a directory (recursively).
+    fn dir_size(dir: &Path) -> Result<u64> {
+        let mut size = 0;
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+
+            if metadata.is_dir() {
+                size += Self::dir_size(&entry.path())?;
+            } else {
+                size += metadata.len();
+            }
+        }
+
+        Ok(size)
+    }
+
+    /// Save `time` as the time of the last update.
+    fn write_last_update(&self, time: SystemTime) -> Result<()> {
+        let path = self.dir.join(LAST_UPDATE_FILE);
+        let secs = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        fs::write(&path, secs.to_string()).path_context(&path)?;
+        Ok(())
+    }
+
     /// Get the age of the cache.
     pub fn age(&self) -> Result<Duration> {
         self.age
             .get_or_try_init(|| {
+                let last_update = fs::read_to_string(self.dir.join(LAST_UPDATE_FILE))
+                    .ok()
+                    .and_then(|x| x.trim().parse().ok())
+                    .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+
+                if let Some(time) = last_update {
+                    return time.elapsed().map_err(|_| Error::clock_error());
+                }
+
+                // Caches created by older versions do not have the file.
                 let sumfile = self.dir.join("tldr.sha256sums");
                 let metadata = if sumfile.is_file() {
                     fs::metadata(&sumfile)
@@ -575,13 +1823,10 @@ impl<'a> Cache<'a> {
                     fs::metadata(self.dir)
                 }?;
 
-                metadata.modified()?.elapsed().map_err(|_| {
-                    Error::new(
-                        "the system clock is not functioning correctly.\n\
-                        Modification time of the cache is later than the current system time.\n\
-                        Please fix your system clock.",
-                    )
-                })
+                metadata
+                    .modified()?
+                    .elapsed()
+                    .map_err(|_| Error::clock_error())
             })
             .copied()
     }
@@ -1,18 +1,62 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io::{self, Write};
+use std::mem;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
-use yansi::{Color, Style};
+use yansi::{Color, Paint, Style};
 
 use crate::cache::Cache;
 use crate::error::{Error, ErrorKind, Result};
-use crate::util::{self, warnln};
+use crate::util::{self, debugln, warnln};
 
-#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+/// The source of this file. Doc comments of config options are taken from it
+/// to generate a commented config.
+const SOURCE: &str = include_str!("config.rs");
+
+/// Get the doc comments of the fields of a struct defined in this file (field name => lines).
+fn field_docs(struct_name: &str) -> HashMap<&'static str, Vec<&'static str>> {
+    let start = format!("pub struct {struct_name} {{");
+    let mut result = HashMap::new();
+    let mut doc = vec![];
+
+    for line in SOURCE.lines().skip_while(|x| *x != start).skip(1) {
+        let line = line.trim();
+        if line == "}" {
+            break;
+        }
+
+        if let Some(doc_line) = line.strip_prefix("///") {
+            doc.push(doc_line.trim());
+        } else if let Some((field, _)) = line
+            .trim_start_matches("pub ")
+            .split_once(':')
+            .filter(|_| !line.starts_with("#["))
+        {
+            result.insert(field, mem::take(&mut doc));
+        }
+    }
+
+    result
+}
+
+/// Get the name of the struct that represents a table in the config (e.g. "cache" => `CacheConfig`).
+fn table_struct(table: &str) -> Option<&'static str> {
+    match table {
+        "" => Some("Config"),
+        "cache" => Some("CacheConfig"),
+        "output" => Some("OutputConfig"),
+        "indent" => Some("IndentConfig"),
+        "style" => Some("StyleConfig"),
+        _ => None,
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum OutputColor {
     Black,
@@ -29,6 +73,16 @@ pub enum OutputColor {
     Rgb([u8; 3]),
 }
 
+impl OutputColor {
+    /// Convert RGB colors to the 256-color palette.
+    fn downgrade_rgb(self) -> Self {
+        match self {
+            Self::Rgb(rgb) => Self::Color256(util::rgb_to_256(rgb)),
+            c => c,
+        }
+    }
+}
+
 impl From<OutputColor> for yansi::Color {
     fn from(c: OutputColor) -> Self {
         match c {
@@ -47,7 +101,33 @@ impl From<OutputColor> for yansi::Color {
     }
 }
 
+/// How to display placeholders with alternatives (e.g. `{{command|cmd}}`).
 #[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceholderAlternatives {
+    /// Show the whole placeholder.
+    #[default]
+    All,
+    /// Show only the first alternative.
+    First,
+    /// Show only the last alternative.
+    Last,
+}
+
+/// What to show in the page title.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleStyle {
+    /// Show only the command (e.g. `tar`).
+    #[default]
+    CommandOnly,
+    /// Show the platform and the command (e.g. `linux/tar`).
+    PlatformPath,
+    /// Show the language, the platform and the command (e.g. `de linux/tar`).
+    Full,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
 #[serde(deny_unknown_fields, default)]
 pub struct OutputStyle {
     pub color: OutputColor,
@@ -59,6 +139,21 @@ pub struct OutputStyle {
     pub strikethrough: bool,
 }
 
+impl OutputStyle {
+    /// A style with the given foreground color and no other attributes.
+    const fn fg(color: OutputColor) -> Self {
+        Self {
+            color,
+            background: OutputColor::Default,
+            bold: false,
+            underline: false,
+            italic: false,
+            dim: false,
+            strikethrough: false,
+        }
+    }
+}
+
 impl From<OutputStyle> for yansi::Style {
     fn from(s: OutputStyle) -> Self {
         let mut style = Style::new().fg(s.color.into()).bg(s.background.into());
@@ -83,21 +178,183 @@ impl From<OutputStyle> for yansi::Style {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields, default)]
 pub struct StyleConfig {
+    /// A built-in color scheme used as the base for the styles below.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Style for the page title.
     pub title: OutputStyle,
+    /// Style for the page description.
     pub description: OutputStyle,
+    /// Style for example descriptions.
     pub bullet: OutputStyle,
+    /// Style for example commands.
     pub example: OutputStyle,
+    /// Style for URLs inside the description.
     pub url: OutputStyle,
+    /// Style for text inside backticks in descriptions.
     pub inline_code: OutputStyle,
+    /// Style for placeholders inside example commands.
     pub placeholder: OutputStyle,
+    /// Style for environment variables inside example commands (if `output.highlight_env_vars` is enabled).
+    pub env_var: OutputStyle,
+    /// Style for `output.page_separator`.
+    pub separator: OutputStyle,
+}
+
+impl StyleConfig {
+    /// Names of the built-in themes.
+    pub const THEMES: &'static [&'static str] = &["default", "gruvbox", "nord", "monochrome"];
+
+    /// Get a built-in theme by name.
+    fn theme(name: &str) -> Result<Self> {
+        use OutputColor::Rgb;
+
+        let fg = OutputStyle::fg;
+        let theme = match name {
+            "default" => Self::default(),
+            "gruvbox" => Self {
+                theme: None,
+                title: OutputStyle {
+                    bold: true,
+                    ..fg(Rgb([254, 128, 25]))
+                },
+                description: fg(Rgb([211, 134, 155])),
+                bullet: fg(Rgb([184, 187, 38])),
+                example: fg(Rgb([142, 192, 124])),
+                url: OutputStyle {
+                    italic: true,
+                    ..fg(Rgb([131, 165, 152]))
+                },
+                inline_code: OutputStyle {
+                    italic: true,
+                    ..fg(Rgb([250, 189, 47]))
+                },
+                placeholder: OutputStyle {
+                    italic: true,
+                    ..fg(Rgb([251, 73, 52]))
+                },
+                env_var: fg(Rgb([250, 189, 47])),
+                separator: fg(Rgb([146, 131, 116])),
+            },
+            "nord" => Self {
+                theme: None,
+                title: OutputStyle {
+                    bold: true,
+                    ..fg(Rgb([136, 192, 208]))
+                },
+                description: fg(Rgb([129, 161, 193])),
+                bullet: fg(Rgb([163, 190, 140])),
+                example: fg(Rgb([143, 188, 187])),
+                url: OutputStyle {
+                    italic: true,
+                    ..fg(Rgb([180, 142, 173]))
+                },
+                inline_code: OutputStyle {
+                    italic: true,
+                    ..fg(Rgb([235, 203, 139]))
+                },
+                placeholder: OutputStyle {
+                    italic: true,
+                    ..fg(Rgb([191, 97, 106]))
+                },
+                env_var: fg(Rgb([208, 135, 112])),
+                separator: fg(Rgb([76, 86, 106])),
+            },
+            "monochrome" => Self {
+                theme: None,
+                title: OutputStyle {
+                    bold: true,
+                    ..OutputStyle::default()
+                },
+                description: OutputStyle::default(),
+                bullet: OutputStyle::default(),
+                example: OutputStyle {
+                    bold: true,
+                    ..OutputStyle::default()
+                },
+                url: OutputStyle {
+                    underline: true,
+                    ..OutputStyle::default()
+                },
+                inline_code: OutputStyle {
+                    italic: true,
+                    ..OutputStyle::default()
+                },
+                placeholder: OutputStyle {
+                    italic: true,
+                    ..OutputStyle::default()
+                },
+                env_var: OutputStyle {
+                    underline: true,
+                    ..OutputStyle::default()
+                },
+                separator: OutputStyle {
+                    dim: true,
+                    ..OutputStyle::default()
+                },
+            },
+            _ => {
+                return Err(Error::new(format!(
+                    "unknown theme '{name}'. Available themes: {}.",
+                    Self::THEMES.join(", ")
+                ))
+                .kind(ErrorKind::ParseToml))
+            }
+        };
+
+        Ok(theme)
+    }
+
+    /// Load the theme set in the config and apply the options from `style_table` on top of it.
+    fn with_theme(name: &str, style_table: Option<toml::Value>) -> Result<Self> {
+        let toml::Value::Table(mut base) = toml::Value::try_from(Self::theme(name)?).unwrap()
+        else {
+            unreachable!();
+        };
+
+        if let Some(toml::Value::Table(user)) = style_table {
+            for (key, value) in user {
+                match (base.get_mut(&key), value) {
+                    // Merge individual fields of a style (e.g. only `color` in `[style.title]`).
+                    (Some(toml::Value::Table(base_style)), toml::Value::Table(user_style)) => {
+                        base_style.extend(user_style);
+                    }
+                    (_, value) => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+
+        Ok(toml::Value::Table(base).try_into()?)
+    }
+
+    /// Convert RGB colors in all styles to the 256-color palette.
+    fn downgrade_rgb(&mut self) {
+        for style in [
+            &mut self.title,
+            &mut self.description,
+            &mut self.bullet,
+            &mut self.example,
+            &mut self.url,
+            &mut self.inline_code,
+            &mut self.placeholder,
+            &mut self.env_var,
+            &mut self.separator,
+        ] {
+            style.color = style.color.downgrade_rgb();
+            style.background = style.background.downgrade_rgb();
+        }
+    }
 }
 
 impl Default for StyleConfig {
     fn default() -> Self {
         StyleConfig {
+            theme: None,
             title: OutputStyle {
                 color: OutputColor::Magenta,
                 background: OutputColor::default(),
@@ -161,6 +418,24 @@ impl Default for StyleConfig {
                 dim: false,
                 strikethrough: false,
             },
+            env_var: OutputStyle {
+                color: OutputColor::Yellow,
+                background: OutputColor::default(),
+                bold: false,
+                underline: false,
+                italic: false,
+                dim: false,
+                strikethrough: false,
+            },
+            separator: OutputStyle {
+                color: OutputColor::default(),
+                background: OutputColor::default(),
+                bold: false,
+                underline: false,
+                italic: false,
+                dim: true,
+                strikethrough: false,
+            },
         }
     }
 }
@@ -172,13 +447,35 @@ pub struct CacheConfig {
     pub dir: PathBuf,
     /// The mirror of tldr-pages to use.
     pub mirror: Cow<'static, str>,
+    /// Mirrors to use (in order) if downloading from `mirror` fails.
+    pub mirror_fallbacks: Vec<String>,
     /// Automatically update the cache
     /// if it is older than `max_age` hours.
     pub auto_update: bool,
     /// Max cache age in hours.
     max_age: u64,
+    /// Timeout for HTTP requests in seconds (0 disables the timeout).
+    timeout_secs: u64,
+    /// Wait this many milliseconds between downloading archives (0 disables the delay).
+    download_delay_ms: u64,
+    /// Files larger than this (in bytes) are not downloaded.
+    pub max_download_bytes: u64,
     /// Languages to download.
     pub languages: Vec<String>,
+    /// The platform to use when --platform is not specified (overrides the compiled-in default).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// Keep downloaded archives in the cache directory.
+    pub keep_archives: bool,
+    /// Platforms to use for specific pages (page name => platform).
+    /// These are ignored when the platform is specified on the command line.
+    pub page_platform_overrides: HashMap<String, String>,
+    /// Pages to show instead of pages that do not exist (alias => page name).
+    pub aliases: HashMap<String, String>,
+    /// Download single pages that are not in the cache from `on_demand_mirror`.
+    pub on_demand: bool,
+    /// The base URL used for downloading single pages.
+    pub on_demand_mirror: Cow<'static, str>,
 }
 
 impl Default for CacheConfig {
@@ -186,10 +483,23 @@ impl Default for CacheConfig {
         Self {
             dir: Cache::locate(),
             mirror: Cow::Borrowed("https://github.com/tldr-pages/tldr/releases/latest/download"),
+            mirror_fallbacks: vec![],
             auto_update: true,
             // 2 weeks
             max_age: 24 * 7 * 2,
+            timeout_secs: 5,
+            download_delay_ms: 0,
+            // 1 GiB - the archive with all languages is a few MiB.
+            max_download_bytes: 1024 * 1024 * 1024,
             languages: vec![],
+            platform: None,
+            keep_archives: false,
+            page_platform_overrides: HashMap::new(),
+            aliases: HashMap::new(),
+            on_demand: false,
+            on_demand_mirror: Cow::Borrowed(
+                "https://raw.githubusercontent.com/tldr-pages/tldr/main",
+            ),
         }
     }
 }
@@ -199,27 +509,83 @@ impl Default for CacheConfig {
 pub struct OutputConfig {
     /// Show the page title.
     pub show_title: bool,
-    /// Show the platform in the title.
-    pub platform_title: bool,
+    /// What to show in the title: the command only (`command_only`), the platform
+    /// and the command (`platform_path`) or also the language (`full`).
+    pub title_style: TitleStyle,
+    /// Deprecated, use `title_style` instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform_title: Option<bool>,
+    /// Show titles of subcommand pages as a path (e.g. "git › commit").
+    pub breadcrumb_title: bool,
+    /// The separator between commands in a breadcrumb title.
+    pub breadcrumb_separator: Cow<'static, str>,
+    /// Show the first line of the description on the same line as the title.
+    pub title_with_description: bool,
+    /// Join consecutive description lines into a single line.
+    pub join_description: bool,
+    /// Highlight environment variables (`$VAR`, `${VAR}`) in examples.
+    pub highlight_env_vars: bool,
+    /// Which alternatives to show in placeholders like `{{command|cmd}}`.
+    pub placeholder_alternatives: PlaceholderAlternatives,
     /// Show hyphens before example descriptions.
     pub show_hyphens: bool,
-    /// Show a custom string instead of a hyphen.
+    /// Show a custom string instead of a hyphen. `{n}` is replaced with the number of the example.
     pub example_prefix: Cow<'static, str>,
+    /// Number examples (e.g. for use with --copy).
+    pub example_number: bool,
     /// Strip empty lines from pages.
     pub compact: bool,
+    /// Print an empty line after the page (has no effect in compact mode).
+    pub trailing_newline: bool,
+    /// Printed between pages when multiple pages are shown. A single character
+    /// is repeated to fill the width of the terminal (`COLUMNS` or 80 if the output
+    /// is not a terminal).
+    pub page_separator: Cow<'static, str>,
     /// Print pages in raw markdown.
     pub raw_markdown: bool,
+    /// Resolve placeholder alternatives (see `placeholder_alternatives`) in examples
+    /// of raw markdown pages.
+    pub raw_resolve_placeholders: bool,
+    /// Display pages in a pager.
+    pub pager: bool,
+    /// List pages with the same name from other platforms (on stderr).
+    pub show_other_platforms: bool,
+    /// Use RGB colors even if `COLORTERM` does not indicate truecolor support.
+    pub force_truecolor: bool,
+    /// Files larger than this (in bytes) are only rendered with --render
+    /// if they look like a tldr page.
+    pub max_render_bytes: u64,
+    /// Run this command if a page is not found (`{}` is replaced with the page name).
+    /// Only used when a single page would be rendered.
+    pub not_found_command: Cow<'static, str>,
 }
 
 impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             show_title: true,
-            platform_title: false,
+            title_style: TitleStyle::CommandOnly,
+            platform_title: None,
+            breadcrumb_title: false,
+            breadcrumb_separator: Cow::Borrowed(" › "),
+            title_with_description: false,
+            join_description: false,
+            highlight_env_vars: false,
+            placeholder_alternatives: PlaceholderAlternatives::All,
             show_hyphens: false,
             example_prefix: Cow::Borrowed("- "),
+            example_number: false,
             compact: false,
+            trailing_newline: true,
+            page_separator: Cow::Borrowed("─"),
             raw_markdown: false,
+            raw_resolve_placeholders: false,
+            pager: false,
+            show_other_platforms: true,
+            force_truecolor: false,
+            // 1 MiB - the largest tldr pages are a few KiB.
+            max_render_bytes: 1024 * 1024,
+            not_found_command: Cow::Borrowed(""),
         }
     }
 }
@@ -227,9 +593,13 @@ impl Default for OutputConfig {
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields, default)]
 pub struct IndentConfig {
+    /// Indentation of the title.
     pub title: usize,
+    /// Indentation of the description.
     pub description: usize,
+    /// Indentation of example descriptions.
     pub bullet: usize,
+    /// Indentation of example commands.
     pub example: usize,
 }
 
@@ -247,58 +617,173 @@ impl Default for IndentConfig {
 #[derive(Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields, default)]
 pub struct Config {
+    /// Options for downloading and storing pages.
     pub cache: CacheConfig,
+    /// Options for rendering pages.
     pub output: OutputConfig,
+    /// The number of spaces before each part of the page.
     pub indent: IndentConfig,
+    /// Styles of each part of the page. Colors can be one of: "default", "black", "red",
+    /// "green", "yellow", "blue", "magenta", "cyan", "white", an ANSI code (e.g. { color256 = 50 })
+    /// or RGB (e.g. { rgb = [255, 255, 255] }).
     pub style: StyleConfig,
 }
 
 impl Config {
-    fn parse(path: &Path) -> Result<Self> {
-        Ok(toml::from_str(&fs::read_to_string(path).map_err(|e| {
-            Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io)
-        })?)?)
+    /// Parse config files. Options from later files override the ones from earlier files.
+    fn parse(paths: &[PathBuf]) -> Result<Self> {
+        let mut table = toml::Table::new();
+
+        for path in paths {
+            debugln!("using the config file '{}'", path.display());
+            let s = fs::read_to_string(path).map_err(|e| {
+                Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io)
+            })?;
+            // Every file must be valid on its own. This also reports errors with line numbers,
+            // which would be lost after merging.
+            toml::from_str::<Self>(&s)?;
+            Self::merge(&mut table, toml::from_str(&s)?);
+        }
+
+        let mut cfg: Self = toml::Value::Table(table.clone()).try_into()?;
+
+        // The theme must be loaded first, options from the config file are then applied on top of it.
+        if let Some(theme) = cfg.style.theme.take() {
+            debugln!("using the theme '{theme}'");
+            cfg.style = StyleConfig::with_theme(&theme, table.remove("style"))?;
+        }
+
+        Ok(cfg)
+    }
+
+    /// Merge the options from `other` into `base`. Tables are merged recursively,
+    /// other values from `other` replace the ones in `base`.
+    fn merge(base: &mut toml::Table, other: toml::Table) {
+        for (key, value) in other {
+            match (base.get_mut(&key), value) {
+                (Some(toml::Value::Table(base)), toml::Value::Table(other)) => {
+                    Self::merge(base, other);
+                }
+                (_, value) => {
+                    base.insert(key, value);
+                }
+            }
+        }
     }
 
     pub fn new(cli_config_path: Option<PathBuf>) -> Result<Self> {
-        let cfg_res = if let Some(path) = cli_config_path {
+        // --config replaces both the system and the user config.
+        let paths = if let Some(path) = cli_config_path {
             if path.is_file() {
-                Self::parse(&path)
+                vec![path]
             } else {
                 warnln!("'{}': not a file, ignoring --config", path.display());
-                Ok(Self::default())
+                vec![]
             }
         } else {
-            let path = Self::locate();
-            if path.is_file() {
-                Self::parse(&path)
-            } else {
-                Ok(Self::default())
-            }
+            [Self::locate_system(), Some(Self::locate())]
+                .into_iter()
+                .flatten()
+                .filter(|path| path.is_file())
+                .collect()
         };
 
-        cfg_res.map(|mut cfg| {
-            if cfg.cache.languages.is_empty() {
-                util::get_languages_from_env(&mut cfg.cache.languages);
+        let mut cfg = Self::parse(&paths)?;
+        cfg.check_mirrors()?;
+        cfg.migrate_deprecated()?;
+        cfg.validate()?;
+
+        if cfg.cache.languages.is_empty() {
+            util::get_languages_from_env(&mut cfg.cache.languages);
+        } else {
+            util::add_script_variants(&mut cfg.cache.languages);
+        }
+        // English pages should always be downloaded and searched.
+        cfg.cache.languages.push("en".to_string());
+
+        if cfg.cache.dir.starts_with("~") {
+            let mut p = dirs::home_dir().unwrap();
+            p.extend(cfg.cache.dir.components().skip(1));
+            cfg.cache.dir = p;
+        }
+
+        if !cfg.output.force_truecolor && !util::supports_truecolor() {
+            cfg.style.downgrade_rgb();
+        }
+
+        Ok(cfg)
+    }
+
+    /// Convert deprecated options to the ones that replaced them.
+    fn migrate_deprecated(&mut self) -> Result<()> {
+        if let Some(platform_title) = self.output.platform_title.take() {
+            warnln!("output.platform_title is deprecated, use output.title_style instead.");
+            // An explicitly set title_style takes precedence.
+            if self.output.title_style == TitleStyle::CommandOnly && platform_title {
+                self.output.title_style = TitleStyle::PlatformPath;
             }
-            // English pages should always be downloaded and searched.
-            cfg.cache.languages.push("en".to_string());
+        }
+
+        Ok(())
+    }
 
-            if cfg.cache.dir.starts_with("~") {
-                let mut p = dirs::home_dir().unwrap();
-                p.extend(cfg.cache.dir.components().skip(1));
-                cfg.cache.dir = p;
+    /// Check that the mirrors are HTTP(S) URLs and strip trailing slashes from them.
+    fn check_mirrors(&mut self) -> Result<()> {
+        let check = |option: &str, mirror: &str| {
+            if util::is_http_url(mirror) {
+                Ok(mirror.trim_end_matches('/').to_string())
+            } else {
+                Err(Error::new(format!(
+                    "{option}: '{mirror}' is not a valid http or https URL."
+                ))
+                .kind(ErrorKind::ParseToml))
             }
-            cfg
-        })
+        };
+
+        self.cache.mirror = Cow::Owned(check("cache.mirror", &self.cache.mirror)?);
+        for mirror in &mut self.cache.mirror_fallbacks {
+            *mirror = check("cache.mirror_fallbacks", mirror)?;
+        }
+
+        Ok(())
+    }
+
+    /// Warn about combinations of options that are likely mistakes.
+    /// This never fails because of the options themselves.
+    fn validate(&self) -> Result<()> {
+        if self.cache.auto_update && self.cache.max_age == 0 {
+            warnln!(
+                "cache.max_age is 0 and cache.auto_update is enabled, \
+                the cache will be updated every time tldr is run."
+            );
+        }
+        if self.cache.on_demand && self.cache.on_demand_mirror.trim().is_empty() {
+            warnln!("cache.on_demand is enabled, but cache.on_demand_mirror is empty.");
+        }
+        if !self.output.show_title && self.output.title_with_description {
+            warnln!(
+                "output.title_with_description has no effect when output.show_title is disabled."
+            );
+        }
+        if self.output.raw_markdown && self.style != StyleConfig::default() {
+            warnln!("output.raw_markdown is enabled, the styles from the config are not used.");
+        }
+
+        Ok(())
+    }
+
+    /// Get the path to the system-wide config file, which is loaded before the user's config file.
+    fn locate_system() -> Option<PathBuf> {
+        env::var_os("TLRC_SYSTEM_CONFIG")
+            .map(PathBuf::from)
+            .or_else(|| cfg!(unix).then(|| PathBuf::from("/etc/tlrc/config.toml")))
     }
 
     /// Get the default path to the config file.
     pub fn locate() -> PathBuf {
         env::var_os("TLRC_CONFIG").map_or_else(
             || {
-                dirs::config_dir()
-                    .unwrap()
+                util::base_dir("XDG_CONFIG_HOME", dirs::config_dir(), ".config")
                     .join(env!("CARGO_PKG_NAME"))
                     .join("config.toml")
             },
@@ -306,6 +791,38 @@ impl Config {
         )
     }
 
+    /// Print the config file that is used and the resolved options (for --check-config).
+    ///
+    /// Errors and settings that are likely mistakes are reported by `Config::new`.
+    pub fn check(&self, cli_config_path: Option<&Path>) -> Result<()> {
+        let path = cli_config_path.map_or_else(Self::locate, Path::to_path_buf);
+        let mut stdout = io::stdout().lock();
+
+        // The system config is not used with --config.
+        let system_path =
+            Self::locate_system().filter(|p| cli_config_path.is_none() && p.is_file());
+        if let Some(system_path) = system_path {
+            writeln!(
+                stdout,
+                "System config: {} (OK)",
+                system_path.display().red()
+            )?;
+        }
+        if path.is_file() {
+            writeln!(stdout, "Config: {} (OK)", path.display().red())?;
+        } else {
+            writeln!(stdout, "Config: none (using the default config)")?;
+        }
+        writeln!(stdout, "Cache: {}", self.cache.dir.display().red())?;
+        writeln!(
+            stdout,
+            "Languages: {}",
+            self.cache.languages.join(", ").green().bold()
+        )?;
+
+        Ok(())
+    }
+
     /// Print the default path to the config file and create the config directory.
     pub fn print_path() -> Result<()> {
         let config_path = Config::locate();
@@ -315,7 +832,9 @@ impl Config {
     }
 
     /// Print the default config.
-    pub fn print_default() -> Result<()> {
+    ///
+    /// If `commented` is `true`, every option is preceded by a comment that explains it.
+    pub fn print_default(commented: bool) -> Result<()> {
         let mut cfg = Config::default();
         let home = dirs::home_dir().unwrap();
 
@@ -325,12 +844,66 @@ impl Config {
         }
 
         let cfg = toml::ser::to_string_pretty(&cfg).unwrap();
-        write!(io::stdout(), "{cfg}")?;
+        if !commented {
+            write!(io::stdout(), "{cfg}")?;
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout().lock();
+        let mut table = "";
+
+        for line in cfg.lines() {
+            // Options to document as (table, key). A table header (e.g. "[style.title]")
+            // is documented in the parent table, an option (e.g. "max_age = 336") in the current one.
+            let mut documented = vec![];
+
+            if let Some(header) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+                let (parent, key) = header.rsplit_once('.').unwrap_or(("", header));
+                // The parent table is not printed if it only contains other tables (e.g. "[style]").
+                if !parent.is_empty()
+                    && table != parent
+                    && !table.starts_with(&format!("{parent}."))
+                {
+                    documented.push(("", parent));
+                }
+                documented.push((parent, key));
+                table = header;
+            } else if let Some((key, _)) = line.split_once(" = ") {
+                documented.push((table, key));
+            }
+
+            for (table, key) in documented {
+                let doc = table_struct(table).and_then(|x| field_docs(x).remove(key));
+                for doc_line in doc.unwrap_or_default() {
+                    writeln!(stdout, "# {doc_line}")?;
+                }
+            }
+            writeln!(stdout, "{line}")?;
+        }
+
         Ok(())
     }
 
+    /// Get the primary mirror followed by the fallback mirrors.
+    pub fn mirrors(&self) -> Vec<&str> {
+        let mut mirrors = vec![&*self.cache.mirror];
+        mirrors.extend(self.cache.mirror_fallbacks.iter().map(String::as_str));
+        mirrors
+    }
+
     /// Convert the number of hours from config to a `Duration`.
     pub const fn cache_max_age(&self) -> Duration {
         Duration::from_secs(self.cache.max_age * 60 * 60)
     }
+
+    /// Get the timeout for HTTP requests (`None` if it is disabled).
+    pub fn http_timeout(&self) -> Option<Duration> {
+        (self.cache.timeout_secs != 0).then(|| Duration::from_secs(self.cache.timeout_secs))
+    }
+
+    /// Get the delay between archive downloads (`None` if it is disabled).
+    pub fn download_delay(&self) -> Option<Duration> {
+        (self.cache.download_delay_ms != 0)
+            .then(|| Duration::from_millis(self.cache.download_delay_ms))
+    }
 }
@@ -3,17 +3,37 @@ use std::io::{self, Write};
 use std::path::Path;
 use std::process::ExitCode;
 use std::result::Result as StdResult;
+use std::sync::atomic::Ordering::Relaxed;
 
 use yansi::Paint;
 
+use crate::util;
+
 pub enum ErrorKind {
     ParseToml,
     ParsePage,
     Download,
+    PartialUpdate,
+    StaleCache,
     Io,
     Other,
 }
 
+impl ErrorKind {
+    /// The name of the kind used in JSON error output.
+    const fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::ParseToml => "parse_toml",
+            ErrorKind::ParsePage => "parse_page",
+            ErrorKind::Download => "download",
+            ErrorKind::PartialUpdate => "partial_update",
+            ErrorKind::StaleCache => "stale_cache",
+            ErrorKind::Io => "io",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
 pub struct Error {
     pub kind: ErrorKind,
     message: String,
@@ -36,6 +56,9 @@ impl Error {
         "\n\nThe language you are trying to view the page in is not installed.\n\
         Please update your config and run 'tldr --update' to install a new language.";
 
+    pub const DESC_CORRUPTED_ARCHIVE: &'static str = "\n\nThe archive is corrupted or truncated. \
+        Please run 'tldr --clean-cache' followed by 'tldr --update' to redownload all pages.";
+
     pub fn new<T>(message: T) -> Self
     where
         T: Display,
@@ -87,11 +110,52 @@ impl Error {
         )
     }
 
+    pub fn partial_update(failed_languages: &[String]) -> Self {
+        Error::new(format!(
+            "the following languages could not be updated: {}",
+            failed_languages.join(", ").red().bold()
+        ))
+        .kind(ErrorKind::PartialUpdate)
+    }
+
     pub fn offline_no_cache() -> Self {
         Error::new("cache does not exist. Run tldr without --offline to download pages.")
             .kind(ErrorKind::Download)
     }
 
+    pub fn stale_cache(age: &str) -> Self {
+        Error::new(format!(
+            "cache is stale (last update: {age} ago). Run tldr without --offline to update."
+        ))
+        .kind(ErrorKind::StaleCache)
+    }
+
+    pub fn clock_error() -> Self {
+        Error::new(
+            "the system clock is not functioning correctly.\n\
+            The time of the last cache update is later than the current system time.\n\
+            Please fix your system clock.",
+        )
+    }
+
+    pub fn download_limit(url: &str, limit: u64) -> Self {
+        Error::new(format!(
+            "'{url}' is larger than the download limit ({}).\n\n\
+            The limit can be changed with the 'cache.max_download_bytes' option in the config file.",
+            util::size_fmt(limit).bold()
+        ))
+        .kind(ErrorKind::Download)
+    }
+
+    pub fn extract_archive<T>(lang_dir: &str, e: T) -> Self
+    where
+        T: Display,
+    {
+        Error::new(format!("could not extract '{lang_dir}': {e}"))
+            .kind(ErrorKind::Download)
+            .describe(Error::DESC_CORRUPTED_ARCHIVE)
+    }
+
     pub fn messed_up_cache(e: &str) -> Self {
         Error::new(format!(
             "{e}\n\nThis should never happen, did you delete something from the cache?\n\
@@ -99,20 +163,48 @@ impl Error {
         ))
     }
 
+    /// Serialize the error as a JSON object, without colors.
+    fn to_json(&self) -> String {
+        format!(
+            r#"{{ "error": "{}", "kind": "{}" }}"#,
+            util::json_escape(&self.message),
+            self.kind.as_str()
+        )
+    }
+
     /// Print the error message to stderr and return an appropriate `ExitCode`.
     pub fn exit_code(self) -> ExitCode {
-        let _ = writeln!(io::stderr(), "{} {self}", "error:".red().bold());
+        let mut stderr = io::stderr().lock();
+        if crate::JSON_ERRORS.load(Relaxed) {
+            let _ = writeln!(stderr, "{}", self.to_json());
+        } else {
+            let _ = util::write_timestamp(&mut stderr);
+            let _ = writeln!(stderr, "{} {self}", "error:".red().bold());
+        }
 
         match self.kind {
             ErrorKind::Other | ErrorKind::Io => 1,
             ErrorKind::ParseToml => 3,
             ErrorKind::Download => 4,
             ErrorKind::ParsePage => 5,
+            ErrorKind::PartialUpdate => 6,
+            ErrorKind::StaleCache => 7,
         }
         .into()
     }
 }
 
+/// Add the path that caused an IO error to the error message.
+pub trait IoPathContext<T> {
+    fn path_context(self, path: &Path) -> Result<T>;
+}
+
+impl<T> IoPathContext<T> for io::Result<T> {
+    fn path_context(self, path: &Path) -> Result<T> {
+        self.map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io))
+    }
+}
+
 macro_rules! from_impl {
     ( $from:ty, $kind:tt ) => {
         impl From<$from> for Error {
@@ -5,21 +5,33 @@ mod error;
 mod output;
 mod util;
 
-use std::process::ExitCode;
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::slice;
 use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
 
-use clap::Parser;
 use yansi::Paint;
 
-use crate::args::Cli;
+use crate::args::{Cli, LogFormat, OutputFormat, DEFAULT_PLATFORM};
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::error::{Error, Result};
-use crate::output::PageRenderer;
-use crate::util::{infoln, init_color, warnln};
+use crate::output::{Output, PageRenderer};
+use crate::util::{debugln, infoln, init_color, warnln, DedupIter, PagePathExt};
 
 /// If this is set to true, do not print anything except pages and errors.
 static QUIET: AtomicBool = AtomicBool::new(false);
+/// If this is set to true, print additional messages that are useful for debugging.
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+/// If this is set to true, prefix status messages, warnings and errors with the current time.
+static LOG_TIMESTAMPS: AtomicBool = AtomicBool::new(false);
+/// If this is set to true, print status messages, warnings and debug messages as JSON objects.
+static JSON_LOGS: AtomicBool = AtomicBool::new(false);
+/// If this is set to true, print errors as JSON objects.
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
 
 fn main() -> ExitCode {
     match run() {
@@ -28,113 +40,677 @@ fn main() -> ExitCode {
     }
 }
 
+/// Guess the platform from the environment (for `--platform auto`).
+///
+/// This is best-effort: WSL is detected as `windows`, otherwise the default platform is used.
+fn detect_platform() -> &'static str {
+    let is_wsl = env::var_os("WSL_DISTRO_NAME").is_some()
+        || env::var_os("WSL_INTEROP").is_some()
+        || fs::read_to_string("/proc/version")
+            .is_ok_and(|v| v.to_ascii_lowercase().contains("microsoft"));
+
+    if is_wsl {
+        "windows"
+    } else {
+        DEFAULT_PLATFORM
+    }
+}
+
+/// Resolve platform aliases.
+fn platform_alias(platform: &str) -> &str {
+    if platform == "auto" {
+        return detect_platform();
+    }
+    // "macos" should be an alias of "osx".
+    // Since the `macos` directory doesn't exist, this has to be changed before it
+    // gets passed to cache functions (which expect directory names).
+    if platform == "macos" {
+        "osx"
+    } else {
+        platform
+    }
+}
+
+/// Download the cache if it does not exist or update it if it is stale.
+///
+/// With `strict_offline`, a stale cache is an error when `offline` is set.
+fn auto_update(cache: &Cache, cfg: &Config, offline: bool, strict_offline: bool) -> Result<()> {
+    if cfg.cache.on_demand && !cache.is_downloaded() {
+        debugln!("cache.on_demand is enabled, pages are downloaded when they are needed");
+        return Ok(());
+    }
+
+    if !cache.subdir_exists(cache::ENGLISH_DIR) {
+        if offline {
+            return Err(Error::offline_no_cache());
+        }
+        infoln!("cache is empty, downloading...");
+        cache.update(cfg)?;
+    } else if cfg.cache.auto_update && cache.age()? > cfg.cache_max_age() {
+        let age = util::duration_fmt(cache.age()?.as_secs());
+        let age = age.green().bold();
+
+        if offline && strict_offline {
+            return Err(Error::stale_cache(&age.to_string()));
+        } else if offline {
+            warnln!(
+                "cache is stale (last update: {age} ago). Run tldr without --offline to update."
+            );
+        } else {
+            infoln!("cache is stale (last update: {age} ago), updating...");
+            cache
+                .update(cfg)
+                .map_err(|e| e.describe(Error::DESC_AUTO_UPDATE_ERR))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Override config options with the ones specified on the command line.
+fn include_cli_in_config(cli: &Cli, cfg: &mut Config) {
+    cfg.output.compact = !cli.no_compact && (cli.compact || cfg.output.compact);
+    cfg.output.raw_markdown =
+        !cli.no_raw && (cli.raw || cli.raw_resolved || cfg.output.raw_markdown);
+    cfg.output.raw_resolve_placeholders = cli.raw_resolved || cfg.output.raw_resolve_placeholders;
+    // The pager is only used on a terminal, piped output is written directly (and uncolored).
+    cfg.output.pager = (cli.pager || cfg.output.pager) && io::stdout().is_terminal();
+    cfg.output.show_other_platforms = !cli.no_other_platforms && cfg.output.show_other_platforms;
+    cfg.output.show_title = !cli.no_title && cfg.output.show_title;
+    cfg.cache.auto_update = !cli.no_auto_update && cfg.cache.auto_update;
+
+    if cfg.output.pager {
+        util::init_color_pager(cli.color);
+    }
+}
+
+/// Use pages from `dir` instead of the cache (--pages-dir). The directory is never modified.
+fn use_pages_dir(cfg: &mut Config, dir: &Path) -> Result<()> {
+    if !dir.join(cache::ENGLISH_DIR).is_dir() {
+        return Err(Error::new(format!(
+            "'{}' does not contain a '{}' directory.",
+            dir.display(),
+            cache::ENGLISH_DIR
+        )));
+    }
+
+    cfg.cache.dir = dir.to_path_buf();
+    cfg.cache.on_demand = false;
+    Ok(())
+}
+
+/// Get the platforms from --platform (a comma-separated list in order of priority).
+///
+/// If --platform is not used, `cache.platform` overrides the default platform.
+fn cli_platforms<'a>(cli: &'a Cli, cfg: &'a Config) -> Vec<&'a str> {
+    let platforms = match &cfg.cache.platform {
+        Some(platform) if !cli.platform_is_explicit => platform,
+        _ => &cli.platform,
+    };
+
+    platforms
+        .split(',')
+        .map(platform_alias)
+        .dedup_nosort()
+        .collect()
+}
+
+/// Get the platforms to use for `page_name`, which can be overridden in the config.
+fn page_platforms<'a>(
+    cfg: &'a Config,
+    page_name: &str,
+    platforms: &[&'a str],
+    platform_is_explicit: bool,
+) -> Result<Vec<&'a str>> {
+    match cfg.cache.page_platform_overrides.get(page_name) {
+        Some(override_platform) if !platform_is_explicit => {
+            infoln!("using platform '{override_platform}' for '{page_name}' (set in the config)");
+            Ok(vec![platform_alias(override_platform)])
+        }
+        _ => Ok(platforms.to_vec()),
+    }
+}
+
+/// Create the error shown when a page cannot be found.
+fn page_not_found(
+    cache: &Cache,
+    page_name: &str,
+    languages: &[String],
+    languages_are_from_cli: bool,
+) -> Error {
+    let e = Error::new(format!("page '{page_name}' not found."));
+    if !languages_are_from_cli {
+        return e.describe(Error::desc_page_does_not_exist());
+    }
+
+    let e = e.describe("Try running tldr without --language.");
+    if languages
+        .iter()
+        .all(|x| cache.subdir_exists(&format!("pages.{x}")))
+    {
+        e
+    } else {
+        e.describe(Error::DESC_LANG_NOT_INSTALLED)
+    }
+}
+
+/// Render a file specified with --render.
+fn render_file(path: &Path, output: &mut Output, cli: &Cli, cfg: &Config) -> Result<()> {
+    PageRenderer::check_large_file(path, cfg)?;
+    if cli.select_format {
+        PageRenderer::print_examples(path)
+    } else {
+        PageRenderer::print(path, output, cfg)
+    }
+}
+
+/// Render all files specified with --render.
+///
+/// Unless --strict is used, errors are reported as warnings and the remaining files are rendered.
+fn render_files(cli: &Cli, cfg: &Config) -> Result<()> {
+    if let Some(n) = cli.copy {
+        PageRenderer::copy_example(&cli.render[0], n, cfg)?;
+    }
+
+    // --select-format never uses the pager.
+    let pager = cfg.output.pager && !cli.select_format;
+
+    if let [path] = &cli.render[..] {
+        return Output::with(pager, |output| render_file(path, output, cli, cfg));
+    }
+
+    let mut n_failed = 0;
+
+    // All files are written to the same pager.
+    Output::with(pager, |output| {
+        for path in &cli.render {
+            PageRenderer::print_separator(output, cfg, Some(&path.to_string_lossy()))?;
+
+            if let Err(e) = render_file(path, output, cli, cfg) {
+                if cli.strict {
+                    return Err(e);
+                }
+                warnln!("{e}");
+                n_failed += 1;
+            }
+        }
+        Ok(())
+    })?;
+
+    if n_failed == 0 {
+        Ok(())
+    } else {
+        Err(Error::new(format!(
+            "{n_failed} of {} files could not be rendered.",
+            cli.render.len()
+        )))
+    }
+}
+
+/// Print a diff of the page specified on the command line in two languages (--diff).
+fn diff_page(cli: &Cli, cfg: &Config, cache: &Cache) -> Result<()> {
+    let page_name = cli.page.join("-").to_lowercase();
+    let platforms = page_platforms(
+        cfg,
+        &page_name,
+        &cli_platforms(cli, cfg),
+        cli.platform_is_explicit,
+    )?;
+
+    let mut paths = vec![];
+    for lang in &cli.diff {
+        let path = cache
+            .find(
+                &page_name,
+                slice::from_ref(lang),
+                &platforms,
+                !cli.no_common,
+            )?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                Error::new(format!(
+                    "page '{page_name}' does not exist in language '{lang}'."
+                ))
+                .describe(Error::desc_page_does_not_exist())
+            })?;
+        debugln!("found page: '{}'", path.display());
+        paths.push(path);
+    }
+
+    PageRenderer::print_diff(&paths[0], &paths[1])
+}
+
+/// Get the "More information" URL of the page at `path`.
+fn page_url(path: &Path) -> Result<String> {
+    PageRenderer::page_more_info_url(path)?.ok_or_else(|| {
+        Error::new(format!(
+            "'{}' does not contain a 'More information' link.",
+            path.display()
+        ))
+    })
+}
+
+/// Get the URL of the page at `path` in the tldr repository on GitHub.
+fn page_edit_link(path: &Path) -> Result<String> {
+    let (Some(lang), Some(platform), Some(name)) =
+        (path.page_language(), path.page_platform(), path.page_name())
+    else {
+        return Err(Error::new(format!(
+            "'{}' is not in a language directory of the cache.",
+            path.display()
+        )));
+    };
+    // English pages are in 'pages', not 'pages.en' in the tldr repository.
+    let repo_dir = if lang == "en" {
+        "pages".to_string()
+    } else {
+        format!("pages.{lang}")
+    };
+
+    Ok(format!(
+        "https://github.com/tldr-pages/tldr/blob/main/{repo_dir}/{platform}/{name}.md"
+    ))
+}
+
+/// Open `url` in the default browser.
+fn open_url(url: &str) -> Result<()> {
+    let mut cmd = if cfg!(target_os = "windows") {
+        // The empty argument is the title of the window, otherwise `start` would use the URL.
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+
+    let status = cmd
+        .arg(url)
+        .status()
+        .map_err(|e| Error::new(format!("could not open '{url}': {e}")))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::new(format!("could not open '{url}' ({status}).")))
+    }
+}
+
+/// Open the page in the editor from `VISUAL` or `EDITOR` (--edit).
+fn edit_page(path: &Path, in_cache: bool) -> Result<()> {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .find_map(|var| env::var(var).ok().filter(|x| !x.trim().is_empty()))
+        .ok_or_else(|| Error::new("neither VISUAL nor EDITOR is set, cannot edit the page."))?;
+
+    if fs::metadata(path).is_ok_and(|m| m.permissions().readonly()) {
+        warnln!("'{}' is read-only", path.display());
+    }
+    if in_cache {
+        warnln!("this page is in the cache, changes will be lost when the cache is updated");
+    }
+
+    let mut args = editor.split_whitespace();
+    // This is safe to unwrap, the string is not empty.
+    let program = args.next().unwrap();
+
+    debugln!("running '{editor} {}'", path.display());
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .status()
+        .map_err(|e| Error::new(format!("could not run the editor '{editor}': {e}")))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::new(format!(
+            "the editor '{editor}' failed ({status})."
+        )))
+    }
+}
+
+/// Run `output.not_found_command` for a page that does not exist.
+///
+/// `{}` in the arguments is replaced with the page name. The command is not run in a shell.
+fn run_not_found_command(command: &str, page_name: &str) -> Result<()> {
+    let mut args = command
+        .split_whitespace()
+        .map(|x| x.replace("{}", page_name));
+    // This is safe to unwrap, the command is checked not to be empty.
+    let program = args.next().unwrap();
+
+    debugln!("page '{page_name}' not found, running '{command}'");
+    let status = Command::new(&program)
+        .args(args)
+        .status()
+        .map_err(|e| Error::new(format!("could not run '{program}': {e}")))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::new(format!("'{program}' failed ({status}).")))
+    }
+}
+
+/// Get the language to list pages in (the first one from --language).
+fn list_language(cli: &Cli) -> Option<&str> {
+    cli.languages
+        .as_ref()
+        .and_then(|l| l.first())
+        .map(String::as_str)
+}
+
+/// List pages for the platforms and languages specified on the command line (--list).
+fn list_pages(cli: &Cli, cfg: &Config, cache: &Cache) -> Result<()> {
+    if cli.new {
+        let platforms = cli_platforms(cli, cfg);
+        return cache.list_new(&platforms, !cli.no_common, list_language(cli));
+    }
+    if cli.platform == "all" {
+        return cache.list_by_platform();
+    }
+
+    let platforms = cli_platforms(cli, cfg);
+    match &cli.languages {
+        Some(languages) if cli.platform_is_explicit => {
+            cache.list_for_languages(&platforms, languages)
+        }
+        _ => cache.list_for(&platforms, !cli.no_common, list_language(cli)),
+    }
+}
+
+/// Print the number of pages, or the number of variants of a page (--count).
+fn count_pages(cli: &Cli, cfg: &Config, cache: &Cache, page: &[String]) -> Result<()> {
+    if page.is_empty() {
+        let platforms = cli_platforms(cli, cfg);
+        return cache.count_for(&platforms, !cli.no_common, list_language(cli));
+    }
+
+    let page_name = page.join("-").to_lowercase();
+    cache.count_variants(&page_name, cli.languages.as_deref())
+}
+
+/// Find a page in the cache, or download it if `cache.on_demand` is enabled.
+fn find_page(
+    cli: &Cli,
+    cfg: &Config,
+    cache: &Cache,
+    page_name: &str,
+    languages: &[String],
+    platforms: &[&str],
+) -> Result<Vec<PathBuf>> {
+    let platforms = page_platforms(cfg, page_name, platforms, cli.platform_is_explicit)?;
+    let mut page_paths = if cfg.cache.on_demand {
+        // The cache might not contain the platform (or any pages) yet.
+        cache
+            .find(page_name, languages, &platforms, !cli.no_common)
+            .unwrap_or_default()
+    } else {
+        cache.find(page_name, languages, &platforms, !cli.no_common)?
+    };
+    if page_paths.is_empty() && cfg.cache.on_demand && !cli.offline {
+        page_paths.extend(cache.fetch_page(
+            cfg,
+            page_name,
+            languages,
+            &platforms,
+            !cli.no_common,
+        )?);
+    }
+    for path in &page_paths {
+        debugln!("found page: '{}'", path.display());
+    }
+
+    Ok(page_paths)
+}
+
+/// Find and print the pages specified on the command line.
+fn show_pages(cli: &Cli, cfg: &Config, cache: &Cache, languages: &[String]) -> Result<()> {
+    let languages_are_from_cli = cli.languages.is_some();
+    let platforms = cli_platforms(cli, cfg);
+
+    // With --multi, every argument is a separate page. Otherwise, the arguments are
+    // joined with hyphens to form the name of a single page ("git commit" => "git-commit").
+    let page_names = if cli.multi {
+        cli.page.iter().map(|x| x.to_lowercase()).collect()
+    } else {
+        vec![cli.page.join("-").to_lowercase()]
+    };
+
+    // `output.not_found_command` replaces the page, so it is only run
+    // if a single page would be rendered.
+    let run_hook = page_names.len() == 1
+        && !(cli.where_ || cli.url || cli.edit_link || cli.open || cli.edit || cli.select_format)
+        && cli.copy.is_none()
+        && !cfg.output.not_found_command.trim().is_empty();
+
+    // Find all pages first, so that nothing is printed if one of them does not exist.
+    let mut pages = vec![];
+    for page_name in &page_names {
+        let mut page_paths = find_page(cli, cfg, cache, page_name, languages, &platforms)?;
+
+        // Aliases are only used if a page with the requested name does not exist.
+        if let Some(target) = cfg
+            .cache
+            .aliases
+            .get(page_name)
+            .filter(|_| page_paths.is_empty())
+        {
+            let target = target.to_lowercase();
+            page_paths = find_page(cli, cfg, cache, &target, languages, &platforms)?;
+            if !page_paths.is_empty() {
+                infoln!("showing '{target}' (alias of '{page_name}')");
+            }
+        }
+
+        if page_paths.is_empty() {
+            if run_hook {
+                return run_not_found_command(&cfg.output.not_found_command, page_name);
+            }
+            return Err(page_not_found(
+                cache,
+                page_name,
+                languages,
+                languages_are_from_cli,
+            ));
+        }
+        pages.push(page_paths);
+    }
+
+    if cli.where_ {
+        let mut stdout = io::stdout().lock();
+        for path in pages.iter().flatten() {
+            writeln!(stdout, "{}", fs::canonicalize(path)?.display())?;
+        }
+        return Ok(());
+    }
+
+    if cli.url {
+        // --url conflicts with --multi, there is only one page.
+        writeln!(io::stdout(), "{}", page_url(&pages[0][0])?)?;
+        return Ok(());
+    }
+
+    if cli.edit_link {
+        // --edit-link conflicts with --multi, there is only one page.
+        writeln!(io::stdout(), "{}", page_edit_link(&pages[0][0])?)?;
+        return Ok(());
+    }
+
+    if cli.open {
+        // --open conflicts with --multi, there is only one page.
+        let url = page_url(&pages[0][0])?;
+        if cli.dry_run {
+            writeln!(io::stdout(), "{url}")?;
+            return Ok(());
+        }
+        infoln!("opening {url}");
+        return open_url(&url);
+    }
+
+    if cli.edit {
+        // --edit conflicts with --multi, there is only one page.
+        return edit_page(&pages[0][0], cli.pages_dir.is_none());
+    }
+
+    if let Some(n) = cli.copy {
+        // --copy conflicts with --multi, there is only one page.
+        PageRenderer::copy_example(&pages[0][0], n, cfg)?;
+    }
+
+    // The pages are picked before the pager is started, because it takes over the terminal.
+    let mut picked = vec![];
+    for page_paths in &pages {
+        if cli.select_format {
+            picked.push(&*page_paths[0]);
+        } else {
+            picked.push(PageRenderer::pick_cache_result(
+                page_paths,
+                cfg,
+                cli.interactive,
+            )?);
+        }
+    }
+
+    // All pages are written to the same pager. --select-format never uses it.
+    Output::with(cfg.output.pager && !cli.select_format, |output| {
+        for (i, path) in picked.into_iter().enumerate() {
+            if i != 0 {
+                PageRenderer::print_separator(output, cfg, None)?;
+            }
+
+            if cli.select_format {
+                PageRenderer::print_examples(path)?;
+            } else {
+                PageRenderer::print(path, output, cfg)?;
+            }
+        }
+        Ok(())
+    })
+}
+
 fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
+
+    if cli.format == OutputFormat::Json {
+        JSON_ERRORS.store(true, Relaxed);
+    }
+    // This must be set before anything is logged.
+    if cli.log_format == LogFormat::Json {
+        JSON_LOGS.store(true, Relaxed);
+    }
 
     if cli.config_path {
         return Config::print_path();
     }
 
     if cli.gen_config {
-        return Config::print_default();
+        return Config::print_default(cli.commented);
+    }
+
+    if let Some(shell) = cli.gen_completions {
+        return Cli::print_completions(shell);
     }
 
     if cli.quiet {
         QUIET.store(true, Relaxed);
     }
+    if cli.verbose {
+        VERBOSE.store(true, Relaxed);
+    }
+    if cli.log_timestamps {
+        LOG_TIMESTAMPS.store(true, Relaxed);
+    }
 
     init_color(cli.color);
 
-    let mut cfg = Config::new(cli.config)?;
-    cfg.output.compact = !cli.no_compact && (cli.compact || cfg.output.compact);
-    cfg.output.raw_markdown = !cli.no_raw && (cli.raw || cfg.output.raw_markdown);
+    let mut cfg = Config::new(cli.config.clone())?;
+    if cli.check_config {
+        return cfg.check(cli.config.as_deref());
+    }
+    include_cli_in_config(&cli, &mut cfg);
 
-    if let Some(path) = cli.render {
-        return PageRenderer::print(&path, &cfg);
+    if let Some(dir) = &cli.pages_dir {
+        use_pages_dir(&mut cfg, dir)?;
     }
 
-    let languages_are_from_cli = cli.languages.is_some();
-    // We need to clone() because this vector will not be sorted,
-    // unlike the one in the config.
-    let languages = cli.languages.unwrap_or_else(|| cfg.cache.languages.clone());
+    if !cli.render.is_empty() {
+        return render_files(&cli, &cfg);
+    }
+    if cli.stdin_render {
+        return PageRenderer::print_stdin(&cfg);
+    }
+
+    // We need to clone() because the languages from the config are used for updating.
+    let languages = cli
+        .languages
+        .clone()
+        .unwrap_or_else(|| cfg.cache.languages.clone());
     let cache = Cache::new(&cfg.cache.dir);
+    debugln!("cache directory: '{}'", cfg.cache.dir.display());
 
     if cli.clean_cache {
         return cache.clean();
     }
 
-    if cli.update {
-        // update() should never use languages from --language.
-        return cache.update(&cfg.cache.mirror, &mut cfg.cache.languages);
+    if cli.prune_languages {
+        return cache.prune(&cfg.cache.languages, !cli.yes && !cli.quiet);
     }
 
-    if !cache.subdir_exists(cache::ENGLISH_DIR) {
-        if cli.offline {
-            return Err(Error::offline_no_cache());
-        }
-        infoln!("cache is empty, downloading...");
-        cache.update(&cfg.cache.mirror, &mut cfg.cache.languages)?;
-    } else if cfg.cache.auto_update && cache.age()? > cfg.cache_max_age() {
-        let age = util::duration_fmt(cache.age()?.as_secs());
-        let age = age.green().bold();
+    if let Some(path) = &cli.import {
+        return cache.import(path);
+    }
 
-        if cli.offline {
-            warnln!(
-                "cache is stale (last update: {age} ago). Run tldr without --offline to update."
-            );
-        } else {
-            infoln!("cache is stale (last update: {age} ago), updating...");
-            cache
-                .update(&cfg.cache.mirror, &mut cfg.cache.languages)
-                .map_err(|e| e.describe(Error::DESC_AUTO_UPDATE_ERR))?;
+    if cli.update {
+        if cli.dry_run {
+            let languages = cli.languages.as_ref().unwrap_or(&cfg.cache.languages);
+            return cache.preview_update(&cfg, languages);
         }
+        // Without --language, all languages from the config are updated.
+        return match &cli.languages {
+            Some(languages) => cache.update_languages(&cfg, languages),
+            None => cache.update(&cfg),
+        };
     }
 
-    // "macos" should be an alias of "osx".
-    // Since the `macos` directory doesn't exist, this has to be changed before it
-    // gets passed to cache functions (which expect directory names).
-    let platform = if cli.platform == "macos" {
-        "osx"
-    } else {
-        &cli.platform
-    };
+    if cli.pages_dir.is_none() {
+        auto_update(&cache, &cfg, cli.offline, cli.strict_offline)?;
+    }
 
     if cli.list {
-        return cache.list_for(platform);
+        return list_pages(&cli, &cfg, &cache);
     }
     if cli.list_all {
-        return cache.list_all();
+        return cache.list_all(list_language(&cli));
+    }
+    if let Some(page) = &cli.count {
+        return count_pages(&cli, &cfg, &cache, page);
     }
     if cli.info {
+        if cli.format != OutputFormat::Text {
+            return cache.export_stats(cli.format);
+        }
         return cache.info(&cfg);
     }
+    if cli.verify {
+        return cache.verify(&cfg);
+    }
     if cli.list_platforms {
         return cache.list_platforms();
     }
     if cli.list_languages {
+        if cli.format != OutputFormat::Text {
+            return cache.export_languages(&cfg.cache.languages, cli.format);
+        }
         return cache.list_languages();
     }
 
-    let page_name = cli.page.join("-").to_lowercase();
-    let page_paths = cache.find(&page_name, &languages, platform)?;
-
-    if page_paths.is_empty() {
-        let mut e = Error::new("page not found.");
-        return if languages_are_from_cli {
-            e = e.describe("Try running tldr without --language.");
-
-            if !languages
-                .iter()
-                .all(|x| cache.subdir_exists(&format!("pages.{x}")))
-            {
-                e = e.describe(Error::DESC_LANG_NOT_INSTALLED);
-            }
-
-            Err(e)
-        } else {
-            Err(e.describe(Error::desc_page_does_not_exist()))
-        };
+    if !cli.diff.is_empty() {
+        return diff_page(&cli, &cfg, &cache);
     }
 
-    PageRenderer::print_cache_result(&page_paths, &cfg)
+    show_pages(&cli, &cfg, &cache, &languages)
 }
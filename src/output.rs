@@ -1,19 +1,345 @@
 use std::borrow::Cow;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::mem;
 use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
 use std::sync::atomic::Ordering::Relaxed;
 
+use unicode_width::UnicodeWidthStr;
 use yansi::{Paint, Style};
 
-use crate::config::Config;
+use crate::config::{Config, PlaceholderAlternatives, TitleStyle};
 use crate::error::{Error, ErrorKind, Result};
-use crate::util::{warnln, PagePathExt};
+use crate::util::{self, infoln, warnln, PagePathExt};
 
 const TITLE: &str = "# ";
 const DESC: &str = "> ";
 const BULLET: &str = "- ";
 const EXAMPLE: char = '`';
+const TABLE: char = '|';
+/// The UTF-8 byte order mark.
+const BOM: char = '\u{feff}';
+/// Escaped ASCII punctuation is temporarily mapped to this Unicode private use area,
+/// so that it is not interpreted as markup when highlighting.
+const ESCAPE_BASE: u32 = 0xe000;
+
+/// The pager to use if `PAGER` is not set.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// The destination of the page: either standard output or a pager.
+pub enum Output {
+    Stdout(io::StdoutLock<'static>),
+    Pager(Child),
+    /// Discard the output (used to validate pages).
+    Sink(io::Sink),
+}
+
+impl Output {
+    fn new(pager: bool) -> Result<Self> {
+        if !pager {
+            return Ok(Self::Stdout(io::stdout().lock()));
+        }
+
+        let pager_cmd = env::var("PAGER")
+            .ok()
+            .filter(|x| !x.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+        let mut args = pager_cmd.split_whitespace();
+        // This is safe to unwrap, the string is not empty.
+        let program = args.next().unwrap();
+
+        match Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => Ok(Self::Pager(child)),
+            Err(e) => {
+                warnln!("could not start the pager '{pager_cmd}': {e}, printing to stdout instead");
+                Ok(Self::Stdout(io::stdout().lock()))
+            }
+        }
+    }
+
+    /// Close the standard input of the pager and wait for it to exit.
+    fn finish(self) -> Result<()> {
+        if let Self::Pager(mut child) = self {
+            drop(child.stdin.take());
+            child.wait()?;
+        }
+
+        Ok(())
+    }
+
+    /// Create the output (see `Output::new`) and pass it to `f`.
+    ///
+    /// The pager is waited for even if `f` fails, so that the error
+    /// is not printed while the pager is still running.
+    pub fn with<F>(pager: bool, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        let mut output = Self::new(pager)?;
+        let result = f(&mut output);
+        let finished = output.finish();
+        result.and(finished)
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            // The pager is always spawned with a piped stdin.
+            Self::Pager(child) => child.stdin.as_mut().unwrap().write(buf),
+            Self::Sink(sink) => sink.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::Pager(child) => child.stdin.as_mut().unwrap().flush(),
+            Self::Sink(sink) => sink.flush(),
+        }
+    }
+}
+
+/// Copy `s` to the system clipboard.
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(s: &str) -> Result<()> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(s))
+        .map_err(|e| Error::new(format!("could not copy to the clipboard: {e}")))
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_: &str) -> Result<()> {
+    Err(Error::new(
+        "tlrc was built without clipboard support (the 'clipboard' feature).",
+    ))
+}
+
+/// Split a line into parts that are outside and inside angle brackets with URLs
+/// ("More information: <https://example.com>.").
+///
+/// The boolean is `true` if the part is a URL (without the angle brackets).
+/// Unclosed brackets are left as they are.
+fn split_urls(s: &str) -> Vec<(&str, bool)> {
+    let mut result = vec![];
+    let mut rest = s;
+
+    while let Some(start) = rest.find("<http") {
+        let inner = &rest[start + 1..];
+        let Some(end) = inner.find('>') else {
+            // Highlight ending not found.
+            break;
+        };
+
+        if start != 0 {
+            result.push((&rest[..start], false));
+        }
+        result.push((&inner[..end], true));
+        rest = &inner[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        result.push((rest, false));
+    }
+
+    result
+}
+
+/// Split concatenated pages, starting a new page at every title after the first one.
+///
+/// Trailing whitespace is removed from pages that are followed by another page.
+fn split_pages(s: &str) -> Vec<&str> {
+    let mut pages = vec![];
+    let mut start = 0;
+    let mut pos = 0;
+    let mut has_title = false;
+
+    for line in s.split_inclusive('\n') {
+        if line.starts_with(TITLE) {
+            if has_title {
+                pages.push(s[start..pos].trim_end());
+                start = pos;
+            }
+            has_title = true;
+        }
+        pos += line.len();
+    }
+
+    pages.push(&s[start..]);
+    pages
+}
+
+/// Split an example into parts that are outside and inside placeholders.
+///
+/// The boolean is `true` if the part is a placeholder (without the curly braces).
+/// Unclosed placeholders are left as they are.
+fn split_placeholders(s: &str) -> Vec<(&str, bool)> {
+    let mut result = vec![];
+    let mut rest = s;
+
+    while let Some(start) = rest.find("{{") {
+        let inner = &rest[start + 2..];
+
+        // The placeholder ends with the last two braces of the first sequence of closing braces.
+        // This is required for special cases with three or more closing curly braces
+        // ("{{{x}}}" or "{{x}}}"). The extra braces are inside the placeholder.
+        let Some(end) = inner.find("}}").map(|i| {
+            let n_braces = inner[i..].bytes().take_while(|&b| b == b'}').count();
+            i + n_braces - 2
+        }) else {
+            // Highlight ending not found.
+            break;
+        };
+
+        if start != 0 {
+            result.push((&rest[..start], false));
+        }
+        result.push((&inner[..end], true));
+        rest = &inner[end + 2..];
+    }
+
+    if !rest.is_empty() {
+        result.push((rest, false));
+    }
+
+    result
+}
+
+/// Split a part of an example into parts that are outside and inside environment variables.
+///
+/// The boolean is `true` if the part is an environment variable (`$VAR` or `${VAR}`).
+fn split_env_vars(s: &str) -> Vec<(&str, bool)> {
+    let is_var_char = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let bytes = s.as_bytes();
+    let mut result = vec![];
+    let mut last = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'$' {
+            i += 1;
+            continue;
+        }
+
+        let braced = bytes.get(i + 1) == Some(&b'{');
+        let name_start = if braced { i + 2 } else { i + 1 };
+        // Variable names cannot start with a digit.
+        if !bytes
+            .get(name_start)
+            .is_some_and(|&b| b.is_ascii_alphabetic() || b == b'_')
+        {
+            i += 1;
+            continue;
+        }
+
+        let name_len = bytes[name_start..]
+            .iter()
+            .take_while(|&&b| is_var_char(b))
+            .count();
+        let mut end = name_start + name_len;
+
+        if braced {
+            if bytes.get(end) != Some(&b'}') {
+                // Not a valid "${VAR}".
+                i += 1;
+                continue;
+            }
+            end += 1;
+        }
+
+        if i != last {
+            result.push((&s[last..i], false));
+        }
+        result.push((&s[i..end], true));
+        last = end;
+        i = end;
+    }
+
+    if last != s.len() {
+        result.push((&s[last..], false));
+    }
+
+    result
+}
+
+/// Pick one alternative from a placeholder like `{{command|cmd}}`.
+///
+/// Option placeholders (`{{[-s|--long]}}`) and placeholders with whitespace
+/// (which might contain shell pipes) are returned unchanged.
+fn resolve_alternatives(s: &str, mode: PlaceholderAlternatives) -> &str {
+    if !s.contains('|') || s.starts_with('[') || s.contains(char::is_whitespace) {
+        return s;
+    }
+
+    match mode {
+        PlaceholderAlternatives::All => s,
+        // split() always returns at least one item, this is safe to unwrap.
+        PlaceholderAlternatives::First => s.split('|').next().unwrap(),
+        PlaceholderAlternatives::Last => s.rsplit('|').next().unwrap(),
+    }
+}
+
+/// Resolve the alternatives of all placeholders in `s`, keeping the curly braces.
+fn resolve_placeholders(s: &str, mode: PlaceholderAlternatives) -> String {
+    let mut buf = String::new();
+
+    for (part, is_placeholder) in split_placeholders(s) {
+        if is_placeholder {
+            buf += "{{";
+            buf += resolve_alternatives(part, mode);
+            buf += "}}";
+        } else {
+            buf += part;
+        }
+    }
+
+    buf
+}
+
+/// Replace backslash escapes of ASCII punctuation (e.g. `\*`) outside of inline code
+/// with placeholder characters. `restore_escapes` turns them back into the escaped characters.
+fn hide_escapes(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    let mut in_code = false;
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Backslash escapes do not work in inline code.
+            '\\' if !in_code && chars.peek().is_some_and(char::is_ascii_punctuation) => {
+                // This is safe to unwrap, the character was peeked above.
+                let escaped = chars.next().unwrap();
+                // ASCII punctuation + 0xe000 is always a valid char.
+                buf.push(char::from_u32(ESCAPE_BASE + escaped as u32).unwrap());
+            }
+            '`' => {
+                in_code = !in_code;
+                buf.push(c);
+            }
+            c => buf.push(c),
+        }
+    }
+
+    buf
+}
+
+/// Turn the placeholder characters from `hide_escapes` back into ASCII punctuation.
+fn restore_escapes(s: &str) -> String {
+    s.chars()
+        .map(|c| match (c as u32).checked_sub(ESCAPE_BASE) {
+            // This is safe to unwrap, the value is below 0x80.
+            Some(x) if x < 0x80 => char::from_u32(x).unwrap(),
+            _ => c,
+        })
+        .collect()
+}
 
 struct RenderStyles {
     title: Style,
@@ -23,19 +349,30 @@ struct RenderStyles {
     url: Style,
     inline_code: Style,
     placeholder: Style,
+    env_var: Style,
 }
 
 pub struct PageRenderer<'a> {
     /// Path to the page.
     path: &'a Path,
     /// A buffered reader containing the page.
-    reader: BufReader<File>,
-    /// A buffered handle to standard output.
-    stdout: BufWriter<io::StdoutLock<'static>>,
+    reader: Box<dyn BufRead + 'a>,
+    /// A buffered handle to standard output (or the pager).
+    stdout: BufWriter<&'a mut Output>,
     /// The line of the page that is currently being worked with.
     current_line: String,
     /// The line number of the current line.
     lnum: usize,
+    /// The title, if it is printed together with the first line of the description.
+    pending_title: Option<String>,
+    /// The number of examples written so far.
+    n_examples: usize,
+    /// Whether any part of the page has been written (empty lines are only written after that).
+    written: bool,
+    /// Rows of a markdown table. They are written together to align the columns.
+    table: Vec<Vec<String>>,
+    /// Consecutive description lines joined together (if `output.join_description` is enabled).
+    pending_desc: Option<String>,
     /// Style configuration.
     style: RenderStyles,
     /// Other options.
@@ -71,30 +408,26 @@ impl<'a> PageRenderer<'a> {
     }
 
     fn hl_url(&self, s: &str, style_normal: Style) -> String {
-        let split: Vec<&str> = s.split("<http").collect();
-        // Highlight beginning not found.
-        if split.len() == 1 {
-            return s.paint(style_normal).to_string();
+        let mut buf = String::new();
+
+        for (part, is_url) in split_urls(s) {
+            let style = if is_url { self.style.url } else { style_normal };
+            buf += &part.paint(style).to_string();
         }
 
+        buf
+    }
+
+    fn hl_placeholder(&self, s: &str, style_normal: Style) -> String {
         let mut buf = String::new();
 
-        for part in split {
-            if part.contains('>') {
-                // The first part of the second split contains the part to be highlighted.
-                //
-                // "More information: <https://example.com>."
-                // 0: "More information: " => does not match
-                // 1: "s://example.com>."  => 0: "s://example.com" (highlighted)
-                //                            1: ">."
-                let part_split = part.split_once('>').unwrap();
-
-                // "<http" is used to detect URLs. It must be added back.
-                let hl = format!("http{}", part_split.0);
-                buf += &hl.paint(self.style.url).to_string();
-                buf += &part_split.1.paint(style_normal).to_string();
+        for (part, is_placeholder) in split_placeholders(s) {
+            if is_placeholder {
+                let part = resolve_alternatives(part, self.cfg.output.placeholder_alternatives);
+                buf += &part.paint(self.style.placeholder).to_string();
+            } else if self.cfg.output.highlight_env_vars {
+                buf += &self.hl_env_vars(part, style_normal);
             } else {
-                // Highlight ending not found.
                 buf += &part.paint(style_normal).to_string();
             }
         }
@@ -102,35 +435,13 @@ impl<'a> PageRenderer<'a> {
         buf
     }
 
-    fn hl_placeholder(&self, s: &str, style_normal: Style) -> String {
-        let split: Vec<&str> = s.split("{{").collect();
-        // Highlight beginning not found.
-        if split.len() == 1 {
-            return s.paint(style_normal).to_string();
-        }
-
+    fn hl_env_vars(&self, s: &str, style_normal: Style) -> String {
         let mut buf = String::new();
 
-        for part in split {
-            if part.contains("}}") {
-                // The first part of the second split contains the part to be highlighted.
-                //
-                // "aa bb {{cc}} {{dd}} ee"
-                // 0: "aa bb "   => does not match
-                // 1: "cc}} "    => 0: "cc"    (highlighted)
-                //                  1: "}}"
-                // 2: "dd}} ee"  => 0: "dd"    (highlighted)
-                //                  1: "}} ee"
-
-                // This is required for special cases with three closing curly braces ("}}}").
-                // The first brace is inside the placeholder, and the last two mark the end of it.
-                let idx = part.rmatch_indices("}}").last().unwrap().0;
-                let part_split = part.split_at(idx);
-
-                buf += &part_split.0.paint(self.style.placeholder).to_string();
-                buf += &part_split.1[2..].paint(style_normal).to_string();
+        for (part, is_env_var) in split_env_vars(s) {
+            if is_env_var {
+                buf += &part.paint(self.style.env_var).to_string();
             } else {
-                // Highlight ending not found.
                 buf += &part.paint(style_normal).to_string();
             }
         }
@@ -138,24 +449,282 @@ impl<'a> PageRenderer<'a> {
         buf
     }
 
-    /// Print or render the page according to the provided config.
-    pub fn print(path: &'a Path, cfg: &'a Config) -> Result<()> {
+    /// Print or render the page to `output` according to the provided config.
+    pub fn print(path: &'a Path, output: &'a mut Output, cfg: &'a Config) -> Result<()> {
         let mut page = File::open(path)
             .map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io))?;
 
         if cfg.output.raw_markdown {
-            io::copy(&mut page, &mut io::stdout()).map_err(|e| {
-                Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io)
-            })?;
+            return if cfg.output.raw_resolve_placeholders {
+                Self::copy_resolved(BufReader::new(page), output, cfg)
+            } else {
+                io::copy(&mut page, output).map(|_| ())
+            }
+            .map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io));
+        }
+
+        Self::new(path, Box::new(BufReader::new(page)), output, cfg).render()
+    }
+
+    /// Copy a raw page, resolving placeholder alternatives in example lines.
+    fn copy_resolved(mut page: impl BufRead, output: &mut Output, cfg: &Config) -> io::Result<()> {
+        let mut line = String::new();
+
+        while page.read_line(&mut line)? != 0 {
+            if line.starts_with(EXAMPLE) {
+                let resolved = resolve_placeholders(&line, cfg.output.placeholder_alternatives);
+                output.write_all(resolved.as_bytes())?;
+            } else {
+                output.write_all(line.as_bytes())?;
+            }
+            String::clear(&mut line);
+        }
+
+        Ok(())
+    }
+
+    /// Render pages read from standard input (for --stdin-render).
+    ///
+    /// Every title after the first one starts a new page, so multiple concatenated pages
+    /// are rendered separately, with `output.page_separator` between them.
+    pub fn print_stdin(cfg: &Config) -> Result<()> {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| Error::new(format!("could not read standard input: {e}")))?;
+        let path = Path::new("<stdin>");
+        let mut output = Output::Stdout(io::stdout().lock());
+
+        for (i, page) in split_pages(&input).into_iter().enumerate() {
+            if i != 0 {
+                Self::print_separator(&mut output, cfg, None)?;
+            }
+
+            if cfg.output.raw_markdown {
+                output.write_all(page.as_bytes())?;
+                output.flush()?;
+                continue;
+            }
+
+            PageRenderer::new(path, Box::new(page.as_bytes()), &mut output, cfg).render()?;
+        }
+
+        Ok(())
+    }
+
+    /// Return an error if the file is larger than `output.max_render_bytes`
+    /// and does not start like a tldr page.
+    ///
+    /// This prevents accidentally parsing huge files (e.g. logs) with --render.
+    pub fn check_large_file(path: &Path, cfg: &Config) -> Result<()> {
+        let size = path
+            .metadata()
+            .map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io))?
+            .len();
+
+        if size <= cfg.output.max_render_bytes {
             return Ok(());
         }
 
+        let page = File::open(path)
+            .map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io))?;
+
+        // The title should be the first non-empty line. Read only a few lines,
+        // because the file might not even contain newlines.
+        let mut first_line = String::new();
+        for line in BufReader::new(page.take(4096)).lines().take(5) {
+            let Ok(line) = line else {
+                break;
+            };
+            if !line.trim().is_empty() {
+                first_line = line;
+                break;
+            }
+        }
+
+        if first_line.trim_start_matches(BOM).starts_with(TITLE) {
+            Ok(())
+        } else {
+            Err(Error::new(format!(
+                "'{}' is too large ({}) and does not look like a tldr page.",
+                path.display(),
+                util::size_fmt(size),
+            ))
+            .describe(format!(
+                "If this is a tldr page, increase '{}' in the config.",
+                "output.max_render_bytes".bold()
+            ))
+            .kind(ErrorKind::ParsePage))
+        }
+    }
+
+    /// Get the description and the command of every example in the page.
+    fn examples(path: &Path) -> Result<Vec<(String, String)>> {
+        let page = fs::read_to_string(path)
+            .map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io))?;
+        let mut examples = vec![];
+        let mut desc = "";
+
+        for (i, line) in page.lines().enumerate() {
+            let line = line.trim_end();
+
+            if let Some(bullet) = line.strip_prefix(BULLET) {
+                desc = bullet.strip_suffix(':').unwrap_or(bullet);
+            } else if let Some(example) = line.strip_prefix(EXAMPLE) {
+                let example = example.strip_suffix('`').ok_or_else(|| {
+                    Error::parse_page(path, i + 1, line)
+                        .describe("\nEvery line with an example must end with a backtick '`'.")
+                })?;
+                examples.push((desc.to_string(), example.to_string()));
+            }
+        }
+
+        Ok(examples)
+    }
+
+    /// Get the first URL from the description of the page (usually the "More information" link).
+    pub fn page_more_info_url(path: &Path) -> Result<Option<String>> {
+        let page = fs::read_to_string(path)
+            .map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io))?;
+
+        Ok(page
+            .lines()
+            .filter_map(|line| line.strip_prefix(DESC))
+            .flat_map(split_urls)
+            .find_map(|(part, is_url)| is_url.then(|| part.to_string())))
+    }
+
+    /// Print every example of the page as a single line: `description<TAB>command`.
+    ///
+    /// The output is never colored, so that it can be piped to tools like `fzf`.
+    pub fn print_examples(path: &Path) -> Result<()> {
+        let mut stdout = BufWriter::new(io::stdout().lock());
+
+        for (desc, example) in Self::examples(path)? {
+            writeln!(stdout, "{desc}\t{example}")?;
+        }
+
+        Ok(stdout.flush()?)
+    }
+
+    /// Print a unified diff of two pages (e.g. the same page in two languages).
+    ///
+    /// Empty lines are ignored. Lines are compared exactly, so usually only commands
+    /// in examples match between languages.
+    pub fn print_diff(old_path: &Path, new_path: &Path) -> Result<()> {
+        let read = |path: &Path| {
+            fs::read_to_string(path)
+                .map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io))
+        };
+        let old_page = read(old_path)?;
+        let new_page = read(new_path)?;
+        let old: Vec<&str> = old_page
+            .lines()
+            .map(str::trim_end)
+            .filter(|x| !x.is_empty())
+            .collect();
+        let new: Vec<&str> = new_page
+            .lines()
+            .map(str::trim_end)
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..].
+        let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut stdout = BufWriter::new(io::stdout().lock());
+        writeln!(stdout, "{}", format!("--- {}", old_path.display()).bold())?;
+        writeln!(stdout, "{}", format!("+++ {}", new_path.display()).bold())?;
+        writeln!(
+            stdout,
+            "{}",
+            format!("@@ -1,{} +1,{} @@", old.len(), new.len()).cyan()
+        )?;
+
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                writeln!(stdout, " {}", old[i])?;
+                i += 1;
+                j += 1;
+            } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                writeln!(stdout, "{}", format!("-{}", old[i]).red())?;
+                i += 1;
+            } else {
+                writeln!(stdout, "{}", format!("+{}", new[j]).green())?;
+                j += 1;
+            }
+        }
+
+        Ok(stdout.flush()?)
+    }
+
+    /// Copy the command from the `n`th example of the page (counting from 1) to the clipboard.
+    ///
+    /// Curly braces are removed from placeholders, like in the rendered page.
+    pub fn copy_example(path: &Path, n: usize, cfg: &Config) -> Result<()> {
+        let examples = Self::examples(path)?;
+        let Some((_, example)) = n.checked_sub(1).and_then(|i| examples.get(i)) else {
+            return Err(Error::new(format!(
+                "example {n} does not exist. The page has {} examples.",
+                examples.len()
+            )));
+        };
+
+        let command: String = split_placeholders(example)
+            .into_iter()
+            .map(|(part, is_placeholder)| {
+                if is_placeholder {
+                    resolve_alternatives(part, cfg.output.placeholder_alternatives)
+                } else {
+                    part
+                }
+            })
+            .collect();
+
+        copy_to_clipboard(&command)?;
+        infoln!("copied to the clipboard: {command}");
+        Ok(())
+    }
+
+    /// Check if the page is valid without printing it.
+    pub fn validate(path: &'a Path, cfg: &'a Config) -> Result<()> {
+        let page = File::open(path)
+            .map_err(|e| Error::new(format!("'{}': {e}", path.display())).kind(ErrorKind::Io))?;
+
+        let mut sink = Output::Sink(io::sink());
+        // The renderer borrows `sink`, so it must be dropped before returning.
+        let result =
+            PageRenderer::new(path, Box::new(BufReader::new(page)), &mut sink, cfg).render();
+        result
+    }
+
+    fn new(
+        path: &'a Path,
+        reader: Box<dyn BufRead + 'a>,
+        output: &'a mut Output,
+        cfg: &'a Config,
+    ) -> Self {
         Self {
             path,
-            reader: BufReader::new(page),
-            stdout: BufWriter::new(io::stdout().lock()),
+            reader,
+            stdout: BufWriter::new(output),
             current_line: String::new(),
             lnum: 0,
+            pending_title: None,
+            n_examples: 0,
+            written: false,
+            table: vec![],
+            pending_desc: None,
             style: RenderStyles {
                 title: cfg.style.title.into(),
                 desc: cfg.style.description.into(),
@@ -164,15 +733,62 @@ impl<'a> PageRenderer<'a> {
                 url: cfg.style.url.into(),
                 inline_code: cfg.style.inline_code.into(),
                 placeholder: cfg.style.placeholder.into(),
+                env_var: cfg.style.env_var.into(),
             },
             cfg,
         }
-        .render()
     }
 
-    /// Print the first page that was found and warnings for every other page.
-    pub fn print_cache_result(paths: &'a [PathBuf], cfg: &'a Config) -> Result<()> {
-        if !crate::QUIET.load(Relaxed) && paths.len() != 1 {
+    /// Print `output.page_separator`, which separates pages when multiple pages are shown.
+    ///
+    /// If `label` is provided (e.g. a file name), it is shown inside the separator.
+    pub fn print_separator(output: &mut Output, cfg: &Config, label: Option<&str>) -> Result<()> {
+        let indent = " ".repeat(cfg.indent.title);
+        let sep = &*cfg.output.page_separator;
+        let mut chars = sep.chars();
+
+        let line = match (chars.next(), chars.next()) {
+            // A single character is a rule that fills the terminal.
+            (Some(c), None) => {
+                let width = util::terminal_width().saturating_sub(cfg.indent.title * 2);
+                let rule = |n| c.to_string().repeat(n);
+
+                if let Some(label) = label {
+                    let rest = width.saturating_sub(label.chars().count() + 4);
+                    format!("{} {label} {}", rule(2), rule(rest))
+                } else {
+                    rule(width)
+                }
+            }
+            _ => match label {
+                Some(label) if sep.is_empty() => label.to_string(),
+                Some(label) => format!("{sep} {label}"),
+                None => sep.to_string(),
+            },
+        };
+
+        if line.is_empty() {
+            writeln!(output)?;
+        } else {
+            let style = Style::from(cfg.style.separator);
+            writeln!(output, "{indent}{}", line.paint(style))?;
+        }
+        Ok(())
+    }
+
+    /// Print warnings for every page other than the first one that was found,
+    /// and return the page that should be printed (by default the first one).
+    ///
+    /// If `interactive` is `true` and both stdin and stdout are terminals,
+    /// the user is asked which of the pages to print.
+    pub fn pick_cache_result(
+        paths: &'a [PathBuf],
+        cfg: &Config,
+        interactive: bool,
+    ) -> Result<&'a Path> {
+        let mut choice = 0;
+
+        if !crate::QUIET.load(Relaxed) && cfg.output.show_other_platforms && paths.len() != 1 {
             let mut stderr = io::stderr().lock();
             let other_pages = &paths[1..];
             let width = other_pages
@@ -195,11 +811,32 @@ impl<'a> PageRenderer<'a> {
                     format!("{}.", i + 1).green().bold(),
                 )?;
             }
+
+            if interactive && io::stdin().is_terminal() && io::stdout().is_terminal() {
+                choice = Self::ask_choice(other_pages.len())?;
+            }
         }
 
-        // This is safe to unwrap - errors would have already been catched in run().
-        let first = paths.first().unwrap();
-        Self::print(first, cfg)
+        // This is safe to index - errors would have already been catched in run(),
+        // and the choice is at most the number of other pages.
+        Ok(&paths[choice])
+    }
+
+    /// Ask which of the `n` other pages to print. 0 (the first page that was found)
+    /// is returned if the answer is empty or invalid.
+    fn ask_choice(n: usize) -> Result<usize> {
+        let answer = util::ask(&format!("Page to show [1-{n}, Enter for the default]:"))?;
+
+        if answer.is_empty() {
+            return Ok(0);
+        }
+        match answer.parse() {
+            Ok(i) if (1..=n).contains(&i) => Ok(i),
+            _ => {
+                warnln!("invalid choice '{answer}', showing the default page");
+                Ok(0)
+            }
+        }
     }
 
     /// Load the next line into the line buffer.
@@ -213,6 +850,11 @@ impl<'a> PageRenderer<'a> {
             .reader
             .read_line(&mut self.current_line)
             .map_err(|e| Error::new(format!("'{}': {e}", self.path.display())))?;
+        // Pages created on Windows can start with a byte order mark.
+        if self.lnum == 1 && self.current_line.starts_with(BOM) {
+            self.current_line.drain(..BOM.len_utf8());
+        }
+        // This also removes '\r' from CRLF line endings.
         self.current_line
             .truncate(self.current_line.trim_end().len());
         Ok(n)
@@ -225,35 +867,95 @@ impl<'a> PageRenderer<'a> {
         }
         self.add_newline()?;
 
-        let line = self.current_line.strip_prefix(TITLE).unwrap();
-        let title = if self.cfg.output.platform_title {
-            if let Some(platform) = self.path.page_platform() {
-                Cow::Owned(format!("{platform}/{line}"))
-            } else {
-                Cow::Borrowed(line)
-            }
-        } else {
-            Cow::Borrowed(line)
+        let mut line = Cow::Borrowed(self.current_line.strip_prefix(TITLE).unwrap());
+
+        // Show subcommand pages (e.g. "git-commit") as a path: "git › commit".
+        if self.cfg.output.breadcrumb_title
+            && self.path.page_name().is_some_and(|x| x.contains('-'))
+        {
+            line = Cow::Owned(
+                line.split_whitespace()
+                    .collect::<Vec<&str>>()
+                    .join(&self.cfg.output.breadcrumb_separator),
+            );
+        }
+
+        let platform = self.path.page_platform();
+        let title = match (self.cfg.output.title_style, platform) {
+            (TitleStyle::CommandOnly, _) | (_, None) => line,
+            (TitleStyle::Full, Some(platform)) => match self.path.page_language() {
+                Some(lang) => Cow::Owned(format!("{lang} {platform}/{line}")),
+                None => Cow::Owned(format!("{platform}/{line}")),
+            },
+            (TitleStyle::PlatformPath, Some(platform)) => Cow::Owned(format!("{platform}/{line}")),
         };
 
         let title = title.paint(self.style.title);
         let indent = " ".repeat(self.cfg.indent.title);
-        writeln!(self.stdout, "{indent}{title}")?;
+
+        if self.cfg.output.title_with_description {
+            // Wait for the description.
+            self.pending_title = Some(format!("{indent}{title}"));
+        } else {
+            writeln!(self.stdout, "{indent}{title}")?;
+            self.written = true;
+        }
+
+        Ok(())
+    }
+
+    /// Write the title to the page buffer if it is still waiting for the description.
+    fn flush_title(&mut self) -> Result<()> {
+        if let Some(title) = self.pending_title.take() {
+            writeln!(self.stdout, "{title}")?;
+            self.written = true;
+        }
 
         Ok(())
     }
 
     /// Write the current line to the page buffer as a description.
     fn add_desc(&mut self) -> Result<()> {
-        let desc = self.hl_code(
-            &self.hl_url(
-                self.current_line.strip_prefix(DESC).unwrap(),
-                self.style.desc,
-            ),
-            self.style.desc,
-        );
-        let indent = " ".repeat(self.cfg.indent.description);
-        writeln!(self.stdout, "{indent}{desc}")?;
+        let line = self.current_line.strip_prefix(DESC).unwrap();
+
+        if self.cfg.output.join_description {
+            // The description is written by `flush_desc` after its last line.
+            match &mut self.pending_desc {
+                Some(desc) => {
+                    desc.push(' ');
+                    desc.push_str(line);
+                }
+                None => self.pending_desc = Some(line.to_string()),
+            }
+            return Ok(());
+        }
+
+        let line = line.to_string();
+        self.write_desc(&line)
+    }
+
+    /// Write the description joined by `add_desc`.
+    fn flush_desc(&mut self) -> Result<()> {
+        if let Some(desc) = self.pending_desc.take() {
+            self.write_desc(&desc)?;
+        }
+        Ok(())
+    }
+
+    /// Write a description line (on the same line as the title if it is pending).
+    fn write_desc(&mut self, line: &str) -> Result<()> {
+        let line = hide_escapes(line);
+        let desc = self.hl_code(&self.hl_url(&line, self.style.desc), self.style.desc);
+        let desc = restore_escapes(&desc);
+
+        if let Some(title) = self.pending_title.take() {
+            let separator = " — ".paint(self.style.desc);
+            writeln!(self.stdout, "{title}{separator}{desc}")?;
+        } else {
+            let indent = " ".repeat(self.cfg.indent.description);
+            writeln!(self.stdout, "{indent}{desc}")?;
+        }
+        self.written = true;
 
         Ok(())
     }
@@ -261,16 +963,25 @@ impl<'a> PageRenderer<'a> {
     /// Write the current line to the page buffer as a bullet point.
     fn add_bullet(&mut self) -> Result<()> {
         let line = if self.cfg.output.show_hyphens {
-            self.current_line
-                .replace_range(..2, &self.cfg.output.example_prefix);
+            let prefix = &self.cfg.output.example_prefix;
+            if prefix.contains("{n}") {
+                // The example this bullet describes has not been added yet.
+                let prefix = prefix.replace("{n}", &(self.n_examples + 1).to_string());
+                self.current_line.replace_range(..2, &prefix);
+            } else {
+                self.current_line.replace_range(..2, prefix);
+            }
             &self.current_line
         } else {
             self.current_line.strip_prefix(BULLET).unwrap()
         };
 
-        let bullet = self.hl_code(&self.hl_url(line, self.style.bullet), self.style.bullet);
+        let line = hide_escapes(line);
+        let bullet = self.hl_code(&self.hl_url(&line, self.style.bullet), self.style.bullet);
+        let bullet = restore_escapes(&bullet);
         let indent = " ".repeat(self.cfg.indent.bullet);
         writeln!(self.stdout, "{indent}{bullet}")?;
+        self.written = true;
 
         Ok(())
     }
@@ -301,14 +1012,86 @@ impl<'a> PageRenderer<'a> {
             .replace(" \\}\\} ", "}}");
 
         let indent = " ".repeat(self.cfg.indent.example);
-        writeln!(self.stdout, "{indent}{example}")?;
+        self.n_examples += 1;
+
+        if self.cfg.output.example_number {
+            let number = format!("{}. ", self.n_examples);
+            writeln!(
+                self.stdout,
+                "{indent}{}{example}",
+                number.paint(self.style.bullet)
+            )?;
+        } else {
+            writeln!(self.stdout, "{indent}{example}")?;
+        }
+        self.written = true;
+
+        Ok(())
+    }
+
+    /// Add the current line to the table (written by `flush_table`).
+    fn add_table_row(&mut self) {
+        let row = self.current_line.strip_prefix(TABLE).unwrap();
+        let row = row.strip_suffix(TABLE).unwrap_or(row);
+        let cells: Vec<String> = row.split(TABLE).map(|c| c.trim().to_string()).collect();
+
+        // Skip the line between the header and the rest of the table (e.g. "|---|:-:|").
+        let is_delimiter = cells
+            .iter()
+            .all(|c| !c.is_empty() && c.chars().all(|ch| ch == '-' || ch == ':'));
+        if !is_delimiter {
+            self.table.push(cells);
+        }
+    }
+
+    /// Write the table with aligned columns. The first row (the header) uses the bullet style,
+    /// other rows use the description style.
+    fn flush_table(&mut self) -> Result<()> {
+        if self.table.is_empty() {
+            return Ok(());
+        }
+
+        let table = mem::take(&mut self.table);
+        // Backticks are not displayed, they highlight inline code.
+        let cell_width = |cell: &str| cell.replace('`', "").width();
+        let mut widths = vec![];
+        for row in &table {
+            widths.resize(widths.len().max(row.len()), 0);
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell_width(cell));
+            }
+        }
+
+        let indent = " ".repeat(self.cfg.indent.bullet);
+        for (i, row) in table.iter().enumerate() {
+            let style = if i == 0 {
+                self.style.bullet
+            } else {
+                self.style.desc
+            };
+
+            write!(self.stdout, "{indent}")?;
+            for (j, cell) in row.iter().enumerate() {
+                if j != 0 {
+                    write!(self.stdout, "  ")?;
+                }
+                write!(self.stdout, "{}", self.hl_code(cell, style))?;
+                // The last column is not padded to avoid trailing whitespace.
+                if j + 1 != row.len() {
+                    let padding = " ".repeat(widths[j] - cell_width(cell));
+                    write!(self.stdout, "{padding}")?;
+                }
+            }
+            writeln!(self.stdout)?;
+        }
+        self.written = true;
 
         Ok(())
     }
 
     /// Write a newline to the page buffer if compact mode is not turned on.
     fn add_newline(&mut self) -> Result<()> {
-        if !self.cfg.output.compact {
+        if !self.cfg.output.compact && self.written {
             writeln!(self.stdout)?;
         }
 
@@ -318,26 +1101,201 @@ impl<'a> PageRenderer<'a> {
     /// Render the page to standard output.
     fn render(&mut self) -> Result<()> {
         while self.next_line()? != 0 {
+            if !self.current_line.starts_with(DESC) {
+                self.flush_desc()?;
+            }
+            if !self.current_line.starts_with(TABLE) {
+                self.flush_table()?;
+            }
+
             if self.current_line.starts_with(TITLE) {
                 self.add_title()?;
             } else if self.current_line.starts_with(DESC) {
                 self.add_desc()?;
             } else if self.current_line.starts_with(BULLET) {
+                self.flush_title()?;
                 self.add_bullet()?;
             } else if self.current_line.starts_with(EXAMPLE) {
+                self.flush_title()?;
                 self.add_example()?;
+            } else if self.current_line.starts_with(TABLE) {
+                self.flush_title()?;
+                self.add_table_row();
             } else if self.current_line.chars().all(char::is_whitespace) {
-                self.add_newline()?;
+                // Skip the empty line between the title and the description
+                // if they are printed on the same line.
+                if self.pending_title.is_none() {
+                    self.add_newline()?;
+                }
             } else {
                 return Err(
                     Error::parse_page(self.path, self.lnum, &self.current_line).describe(
-                        "\nEvery non-empty line must begin with either '# ', '> ', '- ' or '`'.",
+                        "\nEvery non-empty line must begin with either '# ', '> ', '- ', '`' or '|'.",
                     ),
                 );
             }
         }
 
-        self.add_newline()?;
+        self.flush_desc()?;
+        self.flush_table()?;
+        self.flush_title()?;
+        if self.cfg.output.trailing_newline {
+            self.add_newline()?;
+        }
         Ok(self.stdout.flush()?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(split_placeholders(""), []);
+        assert_eq!(split_placeholders("aa bb"), [("aa bb", false)]);
+        assert_eq!(
+            split_placeholders("aa {{bb}} cc {{dd}}"),
+            [("aa ", false), ("bb", true), (" cc ", false), ("dd", true)]
+        );
+        assert_eq!(split_placeholders("{{}}"), [("", true)]);
+    }
+
+    #[test]
+    fn pages() {
+        assert_eq!(split_pages(""), [""]);
+        assert_eq!(split_pages("# a\n\n> b\n"), ["# a\n\n> b\n"]);
+        assert_eq!(
+            split_pages("# a\n\n> b\n\n# c\n> d\n"),
+            ["# a\n\n> b", "# c\n> d\n"]
+        );
+        // A title in the middle of a line does not start a new page.
+        assert_eq!(split_pages("# a\n`echo # b`\n"), ["# a\n`echo # b`\n"]);
+    }
+
+    #[test]
+    fn urls() {
+        assert_eq!(split_urls("aa bb"), [("aa bb", false)]);
+        assert_eq!(
+            split_urls("More information: <https://example.com>."),
+            [
+                ("More information: ", false),
+                ("https://example.com", true),
+                (".", false)
+            ]
+        );
+        assert_eq!(
+            split_urls("<http://a> <b>"),
+            [("http://a", true), (" <b>", false)]
+        );
+        assert_eq!(split_urls("<https://a"), [("<https://a", false)]);
+    }
+
+    #[test]
+    fn alternatives() {
+        use PlaceholderAlternatives::{All, First, Last};
+
+        assert_eq!(resolve_alternatives("command|cmd", All), "command|cmd");
+        assert_eq!(resolve_alternatives("command|cmd", First), "command");
+        assert_eq!(resolve_alternatives("a|b|c", Last), "c");
+        assert_eq!(resolve_alternatives("file", First), "file");
+        // These are left as they are.
+        assert_eq!(resolve_alternatives("[-s|--long]", First), "[-s|--long]");
+        assert_eq!(resolve_alternatives("cmd1 | cmd2", Last), "cmd1 | cmd2");
+        assert_eq!(resolve_alternatives("a|", First), "a");
+    }
+
+    #[test]
+    fn resolved_placeholders() {
+        use PlaceholderAlternatives::{All, First};
+
+        let s = "`{{command|cmd}} {{[-s|--long]}} {{x}}}`";
+        assert_eq!(resolve_placeholders(s, All), s);
+        assert_eq!(
+            resolve_placeholders(s, First),
+            "`{{command}} {{[-s|--long]}} {{x}}}`"
+        );
+        assert_eq!(resolve_placeholders("{{a|b", First), "{{a|b");
+    }
+
+    #[test]
+    fn option_placeholders() {
+        use PlaceholderAlternatives::{First, Last};
+
+        // Option placeholders are always shown as they are, even if they are malformed.
+        for s in [
+            "[-a|-b|--c]",
+            "[--only-long]",
+            "[-s|--long",
+            "[[-s|--long]]",
+            "[|]",
+            "[",
+        ] {
+            assert_eq!(resolve_alternatives(s, First), s);
+            assert_eq!(resolve_alternatives(s, Last), s);
+        }
+        assert_eq!(
+            split_placeholders("cmd {{[-s|--long}} {{[-a|-b|--c]}}"),
+            [
+                ("cmd ", false),
+                ("[-s|--long", true),
+                (" ", false),
+                ("[-a|-b|--c]", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn env_vars() {
+        assert_eq!(split_env_vars(""), []);
+        assert_eq!(split_env_vars("echo a"), [("echo a", false)]);
+        assert_eq!(
+            split_env_vars("cd $HOME/${XDG_DIR}x"),
+            [
+                ("cd ", false),
+                ("$HOME", true),
+                ("/", false),
+                ("${XDG_DIR}", true),
+                ("x", false)
+            ]
+        );
+        assert_eq!(split_env_vars("$_A1"), [("$_A1", true)]);
+        // Not environment variables.
+        assert_eq!(split_env_vars("$1 $ $$ ${A"), [("$1 $ $$ ${A", false)]);
+        assert_eq!(split_env_vars("${A-b}"), [("${A-b}", false)]);
+        assert_eq!(split_env_vars("ż$Ół"), [("ż$Ół", false)]);
+    }
+
+    #[test]
+    fn escapes() {
+        let hidden = hide_escapes(r"\*a\* \`b\` `c\*` d\e \\");
+        assert!(hidden.starts_with(|c| c != '\\' && c != '*'));
+        assert_eq!(hidden.matches('`').count(), 2);
+        assert_eq!(restore_escapes(&hidden), r"*a* `b` `c\*` d\e \");
+    }
+
+    #[test]
+    fn placeholders_pathological() {
+        assert_eq!(split_placeholders("{{x}}}"), [("x}", true)]);
+        assert_eq!(split_placeholders("{{{x}}}"), [("{x}", true)]);
+        assert_eq!(split_placeholders("{{{{x}}}}"), [("{{x}}", true)]);
+        assert_eq!(
+            split_placeholders("a {{{x}}} b {{y}}"),
+            [("a ", false), ("{x}", true), (" b ", false), ("y", true)]
+        );
+        // Unclosed and stray braces are not placeholders.
+        assert_eq!(split_placeholders("a {{x"), [("a {{x", false)]);
+        assert_eq!(split_placeholders("a {{{"), [("a {{{", false)]);
+        assert_eq!(split_placeholders("a }} b"), [("a }} b", false)]);
+        assert_eq!(
+            split_placeholders("}} {{x}} {{"),
+            [("}} ", false), ("x", true), (" {{", false)]
+        );
+        assert_eq!(split_placeholders("{ {x} }"), [("{ {x} }", false)]);
+        // Multibyte characters must not cause slicing panics.
+        assert_eq!(
+            split_placeholders("ż{{ó}}}ł"),
+            [("ż", false), ("ó}", true), ("ł", false)]
+        );
+    }
+}
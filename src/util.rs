@@ -1,10 +1,14 @@
 use std::borrow::Cow;
 use std::env;
 use std::ffi::OsStr;
-use std::io::{self, IsTerminal};
+use std::fmt::{self, Write as _};
+use std::io::{self, IsTerminal, Write};
 use std::iter;
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use clap::ColorChoice;
 use ring::digest::{digest, SHA256};
@@ -13,11 +17,7 @@ use ring::digest::{digest, SHA256};
 macro_rules! warnln {
     ( $( $arg:tt )* ) => {
         if !$crate::QUIET.load(std::sync::atomic::Ordering::Relaxed) {
-            use std::io::Write;
-            use yansi::Paint;
-            let mut stderr = std::io::stderr().lock();
-            write!(stderr, "{} ", "warning:".yellow().bold())?;
-            writeln!(stderr, $($arg)*)?;
+            $crate::util::write_log("warning", module_path!(), format_args!($($arg)*))?;
         }
     };
 }
@@ -26,11 +26,16 @@ macro_rules! warnln {
 macro_rules! infoln {
     ( $( $arg:tt )* ) => {
         if !$crate::QUIET.load(std::sync::atomic::Ordering::Relaxed) {
-            use std::io::Write;
-            use yansi::Paint;
-            let mut stderr = std::io::stderr().lock();
-            write!(stderr, "{} ", "info:".cyan().bold())?;
-            writeln!(stderr, $($arg)*)?;
+            $crate::util::write_log("info", module_path!(), format_args!($($arg)*))?;
+        }
+    };
+}
+
+/// Prints a debug message if --verbose is used.
+macro_rules! debugln {
+    ( $( $arg:tt )* ) => {
+        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) {
+            $crate::util::write_log("debug", module_path!(), format_args!($($arg)*))?;
         }
     };
 }
@@ -39,11 +44,7 @@ macro_rules! infoln {
 macro_rules! info_start {
     ( $( $arg:tt )* ) => {
         if !$crate::QUIET.load(std::sync::atomic::Ordering::Relaxed) {
-            use std::io::Write;
-            use yansi::Paint;
-            let mut stderr = std::io::stderr().lock();
-            write!(stderr, "{} ", "info:".cyan().bold())?;
-            write!(stderr, $($arg)*)?;
+            $crate::util::write_log_start("info", module_path!(), format_args!($($arg)*))?;
         }
     };
 }
@@ -52,19 +53,157 @@ macro_rules! info_start {
 macro_rules! info_end {
     ( $( $arg:tt )* ) => {
         if !$crate::QUIET.load(std::sync::atomic::Ordering::Relaxed) {
-            use std::io::Write;
-            writeln!(std::io::stderr(), $($arg)*)?;
+            $crate::util::write_log_end(format_args!($($arg)*))?;
         }
     };
 }
 
-pub(crate) use {info_end, info_start, infoln, warnln};
+pub(crate) use {debugln, info_end, info_start, infoln, warnln};
+
+/// A message started with `info_start` that is waiting for `info_end` (with --log-format json).
+static PENDING_LOG: Mutex<Option<(&str, &str, String)>> = Mutex::new(None);
+
+/// Write a status message to stderr, either as text or as a JSON object (--log-format json).
+pub fn write_log(level: &str, target: &str, args: fmt::Arguments) -> io::Result<()> {
+    let mut stderr = io::stderr().lock();
+
+    if crate::JSON_LOGS.load(Relaxed) {
+        return write_json_log(&mut stderr, level, target, &args.to_string());
+    }
+
+    write_timestamp(&mut stderr)?;
+    write_level(&mut stderr, level)?;
+    writeln!(stderr, "{args}")
+}
+
+/// Write the first part of a status message, which is finished by `write_log_end`.
+pub fn write_log_start(
+    level: &'static str,
+    target: &'static str,
+    args: fmt::Arguments,
+) -> io::Result<()> {
+    if crate::JSON_LOGS.load(Relaxed) {
+        // JSON objects cannot be written in parts, the whole message is written by `write_log_end`.
+        *PENDING_LOG.lock().unwrap() = Some((level, target, args.to_string()));
+        return Ok(());
+    }
+
+    let mut stderr = io::stderr().lock();
+    write_timestamp(&mut stderr)?;
+    write_level(&mut stderr, level)?;
+    write!(stderr, "{args}")
+}
+
+/// Finish the status message started by `write_log_start`.
+pub fn write_log_end(args: fmt::Arguments) -> io::Result<()> {
+    let mut stderr = io::stderr().lock();
+
+    if crate::JSON_LOGS.load(Relaxed) {
+        let (level, target, mut message) =
+            PENDING_LOG
+                .lock()
+                .unwrap()
+                .take()
+                .unwrap_or(("info", module_path!(), String::new()));
+        let _ = message.write_fmt(args);
+        return write_json_log(&mut stderr, level, target, &message);
+    }
+
+    writeln!(stderr, "{args}")
+}
+
+/// Write the colored prefix of a message (e.g. "warning: ").
+fn write_level<W>(w: &mut W, level: &str) -> io::Result<()>
+where
+    W: Write,
+{
+    // Not imported in the whole module, `Paint::clear` would conflict with `Vec::clear`.
+    use yansi::Paint;
+
+    let prefix = format!("{level}:");
+    match level {
+        "warning" => write!(w, "{} ", prefix.yellow().bold()),
+        "debug" => write!(w, "{} ", prefix.magenta().bold()),
+        _ => write!(w, "{} ", prefix.cyan().bold()),
+    }
+}
+
+/// Write a message as a JSON object on a single line.
+fn write_json_log<W>(w: &mut W, level: &str, target: &str, message: &str) -> io::Result<()>
+where
+    W: Write,
+{
+    let time = if crate::LOG_TIMESTAMPS.load(Relaxed) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        format!(r#""time": "{}", "#, timestamp_fmt(now))
+    } else {
+        String::new()
+    };
+
+    writeln!(
+        w,
+        r#"{{ {time}"level": "{level}", "target": "{target}", "message": "{}" }}"#,
+        json_escape(message)
+    )
+}
+
+/// Escape `s` for use in a JSON string. ANSI escape sequences (colors) are removed.
+pub fn json_escape(s: &str) -> String {
+    let mut escaped = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Skip ANSI escape sequences (e.g. "\x1b[1m").
+            '\x1b' => {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Write the current time before a message if --log-timestamps is used.
+pub fn write_timestamp<W>(w: &mut W) -> io::Result<()>
+where
+    W: Write,
+{
+    if crate::LOG_TIMESTAMPS.load(Relaxed) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        write!(w, "{} ", timestamp_fmt(now))?;
+    }
+
+    Ok(())
+}
 
 /// Get languages from environment variables according to the tldr client specification.
 pub fn get_languages_from_env(out_vec: &mut Vec<String>) {
     // https://github.com/tldr-pages/tldr/blob/main/CLIENT-SPECIFICATION.md#language
 
-    let Ok(var_lang) = env::var("LANG") else {
+    // LC_ALL and LC_MESSAGES override LANG, like in POSIX locale resolution.
+    let Some(var_lang) = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|name| env::var(name).ok().filter(|x| !x.is_empty()))
+    else {
         return;
     };
 
@@ -77,9 +216,16 @@ pub fn get_languages_from_env(out_vec: &mut Vec<String>) {
         .chain(iter::once(&*var_lang));
 
     for lang in languages {
-        if lang.len() >= 5 && lang.chars().nth(2) == Some('_') {
+        if let Some(script_lang) = lang.get(..7).filter(|x| is_script_code(x)) {
+            // <language>_<Script> (ll_Ssss - 7 characters)
+            out_vec.push(script_lang.to_string());
+            out_vec.push(lang[..2].to_string());
+        } else if lang.len() >= 5 && lang.chars().nth(2) == Some('_') {
             // <language>_<country> (ll_CC - 5 characters)
             out_vec.push(lang[..5].to_string());
+            if let Some(script_lang) = script_variant(&lang[..5]) {
+                out_vec.push(script_lang.to_string());
+            }
             // <language> (ll - 2 characters)
             out_vec.push(lang[..2].to_string());
         } else if lang.len() == 2 {
@@ -88,21 +234,176 @@ pub fn get_languages_from_env(out_vec: &mut Vec<String>) {
     }
 }
 
+/// Return `true` if `lang` is a language code with a script subtag (`ll_Ssss`, e.g. `zh_Hans`).
+fn is_script_code(lang: &str) -> bool {
+    let b = lang.as_bytes();
+    b.len() == 7
+        && b[..2].iter().all(u8::is_ascii_lowercase)
+        && b[2] == b'_'
+        && b[3].is_ascii_uppercase()
+        && b[4..].iter().all(u8::is_ascii_lowercase)
+}
+
+/// Get the script variant of a language code with a country (e.g. `zh_TW` => `zh_Hant`).
+fn script_variant(lang: &str) -> Option<&'static str> {
+    match lang {
+        "zh_CN" | "zh_SG" => Some("zh_Hans"),
+        "zh_TW" | "zh_HK" | "zh_MO" => Some("zh_Hant"),
+        _ => None,
+    }
+}
+
+/// Insert script variants after language codes that have them (e.g. `zh_CN` => `zh_CN`, `zh_Hans`).
+pub fn add_script_variants(languages: &mut Vec<String>) {
+    let mut i = 0;
+
+    while i < languages.len() {
+        if let Some(script_lang) = script_variant(&languages[i]) {
+            if !languages.iter().any(|x| x == script_lang) {
+                i += 1;
+                languages.insert(i, script_lang.to_string());
+            }
+        }
+        i += 1;
+    }
+}
+
+/// Ask the user a question on stderr and return the trimmed answer.
+pub fn ask(question: &str) -> io::Result<String> {
+    let mut stderr = io::stderr().lock();
+    write!(stderr, "{question} ")?;
+    stderr.flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(answer.trim().to_string())
+}
+
+/// Ask the user a yes/no question on stderr and return `true` if the answer is yes.
+pub fn confirm(question: &str) -> io::Result<bool> {
+    let answer = ask(&format!("{question} [y/N]"))?;
+    Ok(matches!(&*answer, "y" | "Y" | "yes" | "Yes"))
+}
+
+/// Return `true` if the `NO_COLOR` environment variable is set to a non-empty string.
+fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|x| !x.is_empty())
+}
+
 /// Initialize color outputting.
 pub fn init_color(color_mode: ColorChoice) {
     match color_mode {
         ColorChoice::Always => {}
         ColorChoice::Never => yansi::disable(),
         ColorChoice::Auto => {
-            let no_color = env::var_os("NO_COLOR").is_some_and(|x| !x.is_empty());
-
-            if no_color || !io::stdout().is_terminal() {
+            if no_color() || !io::stdout().is_terminal() {
                 yansi::disable();
             }
         }
     }
 }
 
+/// Enable color for output written to a pager.
+///
+/// The pager's standard input is never a terminal, so `init_color` might have disabled color.
+/// Colors stay disabled if the user asked for that explicitly (`--color never` or `NO_COLOR`).
+pub fn init_color_pager(color_mode: ColorChoice) {
+    if color_mode == ColorChoice::Auto && !no_color() {
+        yansi::enable();
+    }
+}
+
+/// Get a base directory like the cache or config directory.
+///
+/// On Windows and macOS, `dirs_dir` (from the `dirs` crate) is used. On other systems,
+/// the XDG variable `xdg_var` is preferred if it is an absolute path, and `$HOME/home_subdir`
+/// is used if `dirs` cannot find the directory either.
+pub fn base_dir(xdg_var: &str, dirs_dir: Option<PathBuf>, home_subdir: &str) -> PathBuf {
+    if cfg!(any(target_os = "windows", target_os = "macos")) {
+        return dirs_dir.unwrap();
+    }
+
+    env::var_os(xdg_var)
+        .map(PathBuf::from)
+        // The XDG specification says that relative paths should be ignored.
+        .filter(|x| x.is_absolute())
+        .or(dirs_dir)
+        .or_else(|| {
+            env::var_os("HOME")
+                .filter(|x| !x.is_empty())
+                .map(|home| PathBuf::from(home).join(home_subdir))
+        })
+        .unwrap()
+}
+
+/// Get the width of the terminal.
+///
+/// If stdout is not a terminal (or its size is unknown), `COLUMNS` is used (80 if it is not set).
+pub fn terminal_width() -> usize {
+    stdout_width()
+        .or_else(|| env::var("COLUMNS").ok().and_then(|x| x.parse().ok()))
+        .filter(|&x| x != 0)
+        .unwrap_or(80)
+}
+
+/// Get the number of columns of the terminal connected to stdout.
+#[cfg(unix)]
+fn stdout_width() -> Option<usize> {
+    let stdout = io::stdout();
+    if !stdout.is_terminal() {
+        return None;
+    }
+
+    rustix::termios::tcgetwinsize(stdout)
+        .ok()
+        .map(|size| usize::from(size.ws_col))
+        .filter(|&x| x != 0)
+}
+
+#[cfg(not(unix))]
+fn stdout_width() -> Option<usize> {
+    None
+}
+
+/// Return `true` if the terminal supports 24-bit colors (according to `COLORTERM`).
+pub fn supports_truecolor() -> bool {
+    env::var("COLORTERM").is_ok_and(|x| x == "truecolor" || x == "24bit")
+}
+
+/// Convert an RGB color to the closest color from the 256-color palette.
+pub fn rgb_to_256(rgb: [u8; 3]) -> u8 {
+    // Levels of the 6x6x6 color cube (indexes 16-231).
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let dist = |c: [u8; 3]| -> u32 {
+        rgb.iter()
+            .zip(c)
+            .map(|(&a, b)| u32::from(a.abs_diff(b)).pow(2))
+            .sum()
+    };
+    let nearest_level = |c: u8| {
+        (0..6u8)
+            .min_by_key(|&i| LEVELS[usize::from(i)].abs_diff(c))
+            .unwrap()
+    };
+
+    let cube_idx = rgb.map(nearest_level);
+    let cube = cube_idx.map(|i| LEVELS[usize::from(i)]);
+
+    // The grayscale ramp (indexes 232-255) goes from 8 to 238 in steps of 10.
+    let avg = rgb.iter().map(|&c| u16::from(c)).sum::<u16>() / 3;
+    #[allow(clippy::cast_possible_truncation)]
+    let gray_idx = ((avg.saturating_sub(8) + 5) / 10).min(23) as u8;
+    let gray = 8 + gray_idx * 10;
+
+    if dist([gray; 3]) < dist(cube) {
+        232 + gray_idx
+    } else {
+        16 + 36 * cube_idx[0] + 6 * cube_idx[1] + cube_idx[2]
+    }
+}
+
 pub trait Dedup {
     /// Deduplicate a vector in place preserving the order of elements.
     fn dedup_nosort(&mut self);
@@ -110,34 +411,70 @@ pub trait Dedup {
 
 impl<T> Dedup for Vec<T>
 where
-    T: PartialEq,
+    T: PartialEq + Clone,
 {
     fn dedup_nosort(&mut self) {
-        let old = mem::replace(self, Vec::with_capacity(self.len()));
-        for x in old {
-            if !self.contains(&x) {
-                self.push(x);
-            }
+        *self = mem::take(self).into_iter().dedup_nosort().collect();
+    }
+}
+
+/// An iterator that skips elements which were already yielded. See `DedupIter`.
+pub struct DedupNoSort<I: Iterator> {
+    iter: I,
+    seen: Vec<I::Item>,
+}
+
+impl<I> Iterator for DedupNoSort<I>
+where
+    I: Iterator,
+    I::Item: PartialEq + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The lists we deduplicate (languages, platforms) are short, so a linear search is fine.
+        let x = self.iter.find(|x| !self.seen.contains(x))?;
+        self.seen.push(x.clone());
+        Some(x)
+    }
+}
+
+pub trait DedupIter: Iterator + Sized {
+    /// Deduplicate elements preserving the order in which they first appear.
+    fn dedup_nosort(self) -> DedupNoSort<Self> {
+        DedupNoSort {
+            iter: self,
+            seen: vec![],
         }
     }
 }
 
+impl<I: Iterator> DedupIter for I {}
+
 pub trait PagePathExt {
     /// Extracts the page name from its path.
-    fn page_name(&self) -> Option<Cow<str>>;
+    fn page_name(&self) -> Option<Cow<'_, str>>;
     /// Extracts the platform from the page path.
-    fn page_platform(&self) -> Option<Cow<str>>;
+    fn page_platform(&self) -> Option<Cow<'_, str>>;
+    /// Extracts the language from the page path (`None` if it is not in a `pages.*` directory).
+    fn page_language(&self) -> Option<Cow<'_, str>>;
 }
 
 impl PagePathExt for Path {
-    fn page_name(&self) -> Option<Cow<str>> {
+    fn page_name(&self) -> Option<Cow<'_, str>> {
         self.file_stem().map(OsStr::to_string_lossy)
     }
 
-    fn page_platform(&self) -> Option<Cow<str>> {
+    fn page_platform(&self) -> Option<Cow<'_, str>> {
         self.parent()
             .and_then(|parent| parent.file_name().map(OsStr::to_string_lossy))
     }
+
+    fn page_language(&self) -> Option<Cow<'_, str>> {
+        // Language directories are always valid UTF-8 (e.g. "pages.pt_BR").
+        let lang_dir = self.parent()?.parent()?.file_name()?.to_str()?;
+        lang_dir.strip_prefix("pages.").map(Cow::Borrowed)
+    }
 }
 
 /// Calculates the SHA256 hash and returns a hexadecimal string.
@@ -146,18 +483,76 @@ pub fn sha256_hexdigest(data: &[u8]) -> String {
     let mut hex = String::new();
 
     for part in digest.as_ref() {
-        hex += &format!("{part:02x}");
+        let _ = write!(hex, "{part:02x}");
     }
 
     hex
 }
 
+/// Compute the Levenshtein distance between two strings.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // The distances between the previous prefix of `a` and every prefix of `b`.
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+/// Check if `s` looks like an HTTP(S) URL with a host (e.g. `https://example.org/path`).
+pub fn is_http_url(s: &str) -> bool {
+    let lower = s.to_ascii_lowercase();
+    let Some(rest) = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+    !host.is_empty() && !s.contains(char::is_whitespace)
+}
+
+/// Convert a size in bytes to a human-readable `String`.
+pub fn size_fmt(bytes: u64) -> String {
+    #[allow(clippy::cast_precision_loss)]
+    let kib = bytes as f64 / 1024.0;
+
+    if kib < 1024.0 {
+        format!("{kib:.02} KiB")
+    } else {
+        format!("{:.02} MiB", kib / 1024.0)
+    }
+}
+
+const WEEK: u64 = 604_800;
 const DAY: u64 = 86400;
 const HOUR: u64 = 3600;
 const MINUTE: u64 = 60;
 
 /// Convert time in seconds to a human-readable `String`.
 pub fn duration_fmt(secs: u64) -> String {
+    let weeks = secs / WEEK;
+
+    if weeks != 0 {
+        let days = (secs % WEEK) / DAY;
+
+        return if days == 0 {
+            format!("{weeks}w")
+        } else {
+            format!("{weeks}w, {days}d")
+        };
+    }
+
     let days = secs / DAY;
     let hours = (secs % DAY) / HOUR;
 
@@ -188,23 +583,43 @@ pub fn duration_fmt(secs: u64) -> String {
     }
 }
 
+/// Convert a Unix timestamp to a UTC date and time in the RFC 3339 format.
+pub fn timestamp_fmt(secs: u64) -> String {
+    let (days, secs) = (secs / DAY, secs % DAY);
+    let (hours, minutes, seconds) = (secs / HOUR, secs % HOUR / MINUTE, secs % MINUTE);
+
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}T{hours:02}:{minutes:02}:{seconds:02}Z")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::env;
 
-    fn prepare_env(lang: Option<&str>, language: Option<&str>) {
-        if let Some(lang) = lang {
-            env::set_var("LANG", lang);
+    fn set_env(name: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            env::set_var(name, value);
         } else {
-            env::remove_var("LANG");
+            env::remove_var(name);
         }
+    }
 
-        if let Some(language) = language {
-            env::set_var("LANGUAGE", language);
-        } else {
-            env::remove_var("LANGUAGE");
-        }
+    fn prepare_env(lang: Option<&str>, language: Option<&str>) {
+        set_env("LANG", lang);
+        set_env("LANGUAGE", language);
+        set_env("LC_ALL", None);
+        set_env("LC_MESSAGES", None);
     }
 
     #[test]
@@ -242,6 +657,122 @@ mod tests {
         out_vec.clear();
         get_languages_from_env(&mut out_vec);
         assert_eq!(out_vec, ["de_DE", "de", "pl", "en", "en_US", "en"]);
+
+        prepare_env(Some("zh_TW.UTF-8"), Some("zh_Hans:zh_CN"));
+        out_vec.clear();
+        get_languages_from_env(&mut out_vec);
+        assert_eq!(
+            out_vec,
+            ["zh_Hans", "zh", "zh_CN", "zh_Hans", "zh", "zh_TW", "zh_Hant", "zh"]
+        );
+
+        prepare_env(Some("zh_Hant_HK.UTF-8"), None);
+        out_vec.clear();
+        get_languages_from_env(&mut out_vec);
+        assert_eq!(out_vec, ["zh_Hant", "zh"]);
+
+        // LC_ALL and LC_MESSAGES take precedence over LANG.
+        prepare_env(Some("en_US.UTF-8"), Some("pl"));
+        set_env("LC_MESSAGES", Some("de_DE.UTF-8"));
+        out_vec.clear();
+        get_languages_from_env(&mut out_vec);
+        assert_eq!(out_vec, ["pl", "de_DE", "de"]);
+
+        set_env("LC_ALL", Some("fr_FR.UTF-8"));
+        out_vec.clear();
+        get_languages_from_env(&mut out_vec);
+        assert_eq!(out_vec, ["pl", "fr_FR", "fr"]);
+
+        // An empty variable is ignored.
+        set_env("LC_ALL", Some(""));
+        out_vec.clear();
+        get_languages_from_env(&mut out_vec);
+        assert_eq!(out_vec, ["pl", "de_DE", "de"]);
+
+        // LANGUAGE is used if only LC_MESSAGES is set.
+        prepare_env(None, Some("it"));
+        set_env("LC_MESSAGES", Some("de"));
+        out_vec.clear();
+        get_languages_from_env(&mut out_vec);
+        assert_eq!(out_vec, ["it", "de"]);
+    }
+
+    #[test]
+    fn script_variants() {
+        let mut languages = vec!["zh_TW".to_string(), "de".to_string(), "zh_CN".to_string()];
+        add_script_variants(&mut languages);
+        assert_eq!(languages, ["zh_TW", "zh_Hant", "de", "zh_CN", "zh_Hans"]);
+
+        // Script variants specified by the user are not duplicated.
+        let mut languages = vec!["zh_Hans".to_string(), "zh_SG".to_string()];
+        add_script_variants(&mut languages);
+        assert_eq!(languages, ["zh_Hans", "zh_SG"]);
+
+        assert!(is_script_code("zh_Hans"));
+        assert!(!is_script_code("zh_HANS"));
+        assert!(!is_script_code("zh_TW"));
+    }
+
+    #[test]
+    fn dedup_nosort() {
+        let mut v = vec!["de", "en", "de", "pl", "en", "en"];
+        v.dedup_nosort();
+        assert_eq!(v, ["de", "en", "pl"]);
+
+        // Check many generated vectors with a lot of repeated elements.
+        let mut seed = 12345_u32;
+        for len in 0..200 {
+            let input: Vec<u32> = (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    (seed >> 16) % 10
+                })
+                .collect();
+            let output: Vec<u32> = input.iter().copied().dedup_nosort().collect();
+
+            // Every element appears exactly once...
+            for x in &input {
+                assert_eq!(output.iter().filter(|y| *y == x).count(), 1);
+            }
+            assert!(output.iter().all(|x| input.contains(x)));
+            // ...in the order of first appearance.
+            let first_seen = |x| input.iter().position(|y| *y == x).unwrap();
+            assert!(output
+                .windows(2)
+                .all(|w| first_seen(w[0]) < first_seen(w[1])));
+
+            let mut vec = input.clone();
+            vec.dedup_nosort();
+            assert_eq!(vec, output);
+        }
+    }
+
+    #[test]
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn xdg_base_dir() {
+        let dirs_dir = Some(PathBuf::from("/dirs"));
+
+        set_env("TLRC_TEST_XDG_DIR", Some("/xdg"));
+        assert_eq!(
+            base_dir("TLRC_TEST_XDG_DIR", dirs_dir.clone(), ".cache"),
+            Path::new("/xdg")
+        );
+
+        // Empty and relative paths are ignored.
+        for value in ["", "relative/path"] {
+            set_env("TLRC_TEST_XDG_DIR", Some(value));
+            assert_eq!(
+                base_dir("TLRC_TEST_XDG_DIR", dirs_dir.clone(), ".cache"),
+                Path::new("/dirs")
+            );
+        }
+
+        set_env("TLRC_TEST_XDG_DIR", None);
+        let home = PathBuf::from(env::var_os("HOME").unwrap());
+        assert_eq!(
+            base_dir("TLRC_TEST_XDG_DIR", None, ".cache"),
+            home.join(".cache")
+        );
     }
 
     #[test]
@@ -252,6 +783,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn edit_dist() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("linux", "linux"), 0);
+        assert_eq!(edit_distance("lnux", "linux"), 1);
+        assert_eq!(edit_distance("osx", "os"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("wíndows", "windows"), 1);
+    }
+
+    #[test]
+    fn http_url() {
+        assert!(is_http_url("https://example.org"));
+        assert!(is_http_url("http://127.0.0.1:8000/tldr/"));
+        assert!(is_http_url("HTTPS://example.org"));
+        assert!(!is_http_url("htps://example.org"));
+        assert!(!is_http_url("example.org"));
+        assert!(!is_http_url("https://"));
+        assert!(!is_http_url("https:///path"));
+        assert!(!is_http_url("https://example .org"));
+        assert!(!is_http_url(""));
+    }
+
+    #[test]
+    fn sz_fmt() {
+        assert_eq!(size_fmt(0), "0.00 KiB");
+        assert_eq!(size_fmt(512), "0.50 KiB");
+        assert_eq!(size_fmt(1024), "1.00 KiB");
+        assert_eq!(size_fmt(1024 * 1024), "1.00 MiB");
+        assert_eq!(size_fmt(1024 * 1024 * 3 / 2), "1.50 MiB");
+    }
+
+    #[test]
+    fn rgb_256() {
+        assert_eq!(rgb_to_256([0, 0, 0]), 16);
+        assert_eq!(rgb_to_256([255, 255, 255]), 231);
+        assert_eq!(rgb_to_256([255, 0, 0]), 196);
+        assert_eq!(rgb_to_256([95, 135, 175]), 67);
+        assert_eq!(rgb_to_256([128, 128, 128]), 244);
+        assert_eq!(rgb_to_256([250, 10, 5]), 196);
+    }
+
+    #[test]
+    fn ts_fmt() {
+        assert_eq!(timestamp_fmt(0), "1970-01-01T00:00:00Z");
+        assert_eq!(timestamp_fmt(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(timestamp_fmt(1_700_000_000), "2023-11-14T22:13:20Z");
+        assert_eq!(timestamp_fmt(4_107_542_399), "2100-02-28T23:59:59Z");
+    }
+
     #[test]
     fn dur_fmt() {
         const SECOND: u64 = 1;
@@ -270,5 +852,13 @@ mod tests {
         assert_eq!(duration_fmt(DAY + SECOND), "1d");
         assert_eq!(duration_fmt(DAY + HOUR), "1d, 1h");
         assert_eq!(duration_fmt(DAY + HOUR + SECOND), "1d, 1h");
+        assert_eq!(duration_fmt(6 * DAY + 23 * HOUR), "6d, 23h");
+
+        assert_eq!(duration_fmt(7 * DAY), "1w");
+        assert_eq!(duration_fmt(WEEK + HOUR), "1w");
+        assert_eq!(duration_fmt(WEEK + DAY), "1w, 1d");
+        assert_eq!(duration_fmt(WEEK + DAY + HOUR), "1w, 1d");
+        assert_eq!(duration_fmt(14 * DAY), "2w");
+        assert_eq!(duration_fmt(20 * DAY), "2w, 6d");
     }
 }
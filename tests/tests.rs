@@ -1,11 +1,26 @@
+use std::env;
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::thread;
 
 use assert_cmd::prelude::*;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
 
 const TEST_PAGE: &str = "tests/data/page.md";
 const TEST_PAGE_RENDER: &str = "tests/data/page-render";
 const TEST_PAGE_COMPACT_RENDER: &str = "tests/data/page-compact-render";
+const TEST_PAGE_TITLE_DESC_RENDER: &str = "tests/data/page-title-desc-render";
+const TEST_PAGE_BOM: &str = "tests/data/page-bom.md";
+const TEST_PAGE_ENV_VARS: &str = "tests/data/page-env-vars.md";
+const TEST_PAGE_ENV_VARS_RENDER: &str = "tests/data/page-env-vars-render";
+const TEST_PAGE_TABLE: &str = "tests/data/page-table.md";
+const TEST_PAGE_TABLE_RENDER: &str = "tests/data/page-table-render";
+const TEST_PAGE_ESCAPES: &str = "tests/data/page-escapes.md";
+const TEST_PAGE_ESCAPES_RENDER: &str = "tests/data/page-escapes-render";
 
 fn tlrc() -> Command {
     let mut cmd = Command::cargo_bin("tldr").unwrap();
@@ -13,6 +28,96 @@ fn tlrc() -> Command {
     cmd
 }
 
+/// Serve `files` (path => contents) over HTTP on a random port and return the base URL.
+/// Other paths respond with 404 Not Found.
+fn serve_mirror(files: &'static [(&'static str, &'static str)]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut request_line = String::new();
+            let mut reader = BufReader::new(&stream);
+            reader.read_line(&mut request_line).unwrap();
+            // Skip the headers.
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                line = String::new();
+            }
+
+            let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let response = match files.iter().find(|(p, _)| *p == path) {
+                Some((_, body)) => format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                ),
+                None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string(),
+            };
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    url
+}
+
+/// A temporary cache directory with pages in multiple languages.
+struct TestCache {
+    dir: PathBuf,
+}
+
+impl TestCache {
+    /// Create a cache with the given pages (`language/platform/page`) and a config file pointing to it.
+    fn new(name: &str, pages: &[&str]) -> Self {
+        Self::with_config(name, pages, "")
+    }
+
+    /// Same as `new`, but append `extra_config` to the config file.
+    fn with_config(name: &str, pages: &[&str], extra_config: &str) -> Self {
+        let dir = env::temp_dir().join(format!("tlrc-test-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("cache")).unwrap();
+
+        for page in pages {
+            let (lang, page) = page.split_once('/').unwrap();
+            let path = dir
+                .join("cache")
+                .join(format!("pages.{lang}"))
+                .join(format!("{page}.md"));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::copy(TEST_PAGE, path).unwrap();
+        }
+
+        let config = format!(
+            "[cache]\ndir = '{}'\nauto_update = false\n{extra_config}",
+            dir.join("cache").display()
+        );
+        fs::write(dir.join("config.toml"), config).unwrap();
+
+        Self { dir }
+    }
+
+    fn config(&self) -> PathBuf {
+        self.dir.join("config.toml")
+    }
+
+    fn tlrc(&self) -> Command {
+        let mut cmd = Command::cargo_bin("tldr").unwrap();
+        cmd.args([
+            Path::new("--config"),
+            &self.config(),
+            Path::new("--offline"),
+        ]);
+        cmd
+    }
+}
+
+impl Drop for TestCache {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
 #[test]
 fn raw_md() {
     let expected = fs::read_to_string(TEST_PAGE).unwrap();
@@ -22,6 +127,29 @@ fn raw_md() {
         .stdout(expected);
 }
 
+#[test]
+fn raw_resolved() {
+    let cache = TestCache::with_config(
+        "raw-resolved",
+        &[],
+        "[output]\nplaceholder_alternatives = 'last'\n",
+    );
+    let page = cache.dir.join("page.md");
+    fs::write(
+        &page,
+        "# a\n\n- {{x|y}}:\n\n`a {{file|dir}} {{[-s|--long]}}`\n",
+    )
+    .unwrap();
+
+    cache
+        .tlrc()
+        .arg("--raw-resolved")
+        .arg("--render")
+        .arg(&page)
+        .assert()
+        .stdout("# a\n\n- {{x|y}}:\n\n`a {{dir}} {{[-s|--long]}}`\n");
+}
+
 #[test]
 fn regular_render() {
     let expected = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
@@ -31,6 +159,15 @@ fn regular_render() {
         .stdout(expected);
 }
 
+#[test]
+fn bom_crlf_render() {
+    let expected = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+    tlrc()
+        .args(["--render", TEST_PAGE_BOM])
+        .assert()
+        .stdout(expected);
+}
+
 #[test]
 fn compact_render() {
     let expected = fs::read_to_string(TEST_PAGE_COMPACT_RENDER).unwrap();
@@ -40,6 +177,445 @@ fn compact_render() {
         .stdout(expected);
 }
 
+#[test]
+fn no_title() {
+    let expected = fs::read_to_string(TEST_PAGE_RENDER)
+        .unwrap()
+        .replacen("  test page\n\n", "", 1);
+    tlrc()
+        .args(["--no-title", "--render", TEST_PAGE])
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn edit_link() {
+    let cache = TestCache::new("edit-link", &["en/linux/a", "de/common/b"]);
+    cache
+        .tlrc()
+        .args(["--edit-link", "a"])
+        .assert()
+        .stdout("https://github.com/tldr-pages/tldr/blob/main/pages/linux/a.md\n");
+    cache
+        .tlrc()
+        .args(["--edit-link", "-L", "de", "b"])
+        .assert()
+        .stdout("https://github.com/tldr-pages/tldr/blob/main/pages.de/common/b.md\n");
+}
+
+#[test]
+fn url() {
+    let cache = TestCache::new("url", &["en/linux/a"]);
+    cache
+        .tlrc()
+        .args(["--url", "a"])
+        .assert()
+        .stdout("https://example.org\n");
+
+    fs::write(
+        cache.dir.join("cache/pages.en/linux/a.md"),
+        "# a\n\n> No link.\n",
+    )
+    .unwrap();
+    cache.tlrc().args(["--url", "a"]).assert().failure();
+    cache
+        .tlrc()
+        .args(["--open", "--dry-run", "a"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn open_dry_run() {
+    let cache = TestCache::new("open-dry-run", &["en/linux/a"]);
+    cache
+        .tlrc()
+        .args(["--open", "--dry-run", "a"])
+        .assert()
+        .stdout("https://example.org\n");
+}
+
+#[test]
+fn where_() {
+    let cache = TestCache::new("where", &["en/linux/a", "de/linux/a", "en/osx/a"]);
+    let pages = fs::canonicalize(cache.dir.join("cache")).unwrap();
+
+    cache
+        .tlrc()
+        .args(["--where", "-L", "de", "-L", "en", "a"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}\n{}\n",
+            pages.join("pages.de/linux/a.md").display(),
+            pages.join("pages.en/osx/a.md").display()
+        ));
+
+    cache.tlrc().args(["--where", "b"]).assert().failure();
+}
+
+#[test]
+fn country_language_fallback() {
+    let cache = TestCache::new(
+        "country-language-fallback",
+        &["en/linux/a", "de/linux/a", "pt_BR/linux/a"],
+    );
+    let pages = fs::canonicalize(cache.dir.join("cache")).unwrap();
+
+    // There is no 'pages.de_DE', so 'pages.de' is used.
+    cache
+        .tlrc()
+        .args(["--where", "-L", "de_DE", "a"])
+        .assert()
+        .stdout(format!("{}\n", pages.join("pages.de/linux/a.md").display()));
+    cache
+        .tlrc()
+        .args(["--where", "-L", "pt_BR", "a"])
+        .assert()
+        .stdout(format!(
+            "{}\n",
+            pages.join("pages.pt_BR/linux/a.md").display()
+        ));
+}
+
+#[test]
+fn edit() {
+    let cache = TestCache::new("edit", &["en/linux/a"]);
+    let path = cache.dir.join("cache/pages.en/linux/a.md");
+
+    cache
+        .tlrc()
+        .args(["--edit", "a"])
+        .env_remove("VISUAL")
+        .env("EDITOR", "echo editing")
+        .assert()
+        .success()
+        .stdout(format!("editing {}\n", path.display()));
+
+    cache
+        .tlrc()
+        .args(["--edit", "a"])
+        .env_remove("VISUAL")
+        .env_remove("EDITOR")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn stdin_render() {
+    let page = fs::read_to_string(TEST_PAGE).unwrap();
+    let expected = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+
+    let mut cmd = tlrc();
+    cmd.arg("--stdin-render");
+    assert_cmd::Command::from_std(cmd)
+        .write_stdin(page.clone())
+        .assert()
+        .stdout(expected.clone());
+
+    let mut cmd = tlrc();
+    cmd.env("COLUMNS", "10").arg("--stdin-render");
+    assert_cmd::Command::from_std(cmd)
+        .write_stdin(format!("{page}\n{page}"))
+        .assert()
+        .stdout(format!("{expected}  ──────\n{expected}"));
+}
+
+#[test]
+fn render_multiple() {
+    let out = tlrc()
+        .args(["--render", TEST_PAGE, TEST_PAGE_ENV_VARS])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let page = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+    assert!(stdout.contains(TEST_PAGE));
+    assert!(stdout.contains(TEST_PAGE_ENV_VARS));
+    assert!(stdout.contains(&page));
+
+    let out = tlrc()
+        .args(["--render", "nonexistent.md", TEST_PAGE])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stdout).unwrap().contains(&page));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("1 of 2 files"));
+
+    let out = tlrc()
+        .args(["--strict", "--render", "nonexistent.md", TEST_PAGE])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(!String::from_utf8(out.stdout).unwrap().contains(&page));
+}
+
+#[test]
+fn page_separator() {
+    let cache = TestCache::with_config("page-separator", &[], "[output]\npage_separator = \"=\"\n");
+    let page = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+
+    cache
+        .tlrc()
+        .env("COLUMNS", "30")
+        .args(["--render", TEST_PAGE, TEST_PAGE])
+        .assert()
+        .stdout(format!(
+            "  == {TEST_PAGE} ====\n{page}  == {TEST_PAGE} ====\n{page}"
+        ));
+
+    let cache = TestCache::with_config(
+        "page-separator-string",
+        &[],
+        "[output]\npage_separator = \"***\"\n",
+    );
+    cache
+        .tlrc()
+        .args(["--render", TEST_PAGE, TEST_PAGE])
+        .assert()
+        .stdout(format!(
+            "  *** {TEST_PAGE}\n{page}  *** {TEST_PAGE}\n{page}"
+        ));
+}
+
+#[test]
+fn pager_piped() {
+    let cache = TestCache::with_config("pager-piped", &[], "[output]\npager = true\n");
+    let expected = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+
+    // Output that is not written to a terminal should not go through the pager or be colored.
+    cache
+        .tlrc()
+        .env("PAGER", "cat")
+        .args(["--render", TEST_PAGE])
+        .assert()
+        .stdout(expected.clone());
+    tlrc()
+        .env("PAGER", "cat")
+        .args(["--pager", "--render", TEST_PAGE])
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn select_format() {
+    tlrc()
+        .args([
+            "--color",
+            "always",
+            "--select-format",
+            "--render",
+            TEST_PAGE,
+        ])
+        .assert()
+        .stdout(
+            "This is a description of a `command` example\t\
+            command --opt1 --opt2 {{placeholder}}\n\
+            Another one\tcommand --opt1 {{placeholder1 placeholder2 ...}}\n",
+        );
+}
+
+#[test]
+fn copy_nonexistent_example() {
+    let out = tlrc()
+        .args(["--copy", "3", "--render", TEST_PAGE])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(out.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("The page has 2 examples."));
+
+    tlrc()
+        .args(["--copy", "0", "--render", TEST_PAGE])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn no_trailing_newline() {
+    let cache = TestCache::with_config(
+        "no-trailing-newline",
+        &[],
+        "[output]\ntrailing_newline = false\n",
+    );
+    let expected = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+    let expected = expected.strip_suffix('\n').unwrap();
+
+    cache
+        .tlrc()
+        .args(["--render", TEST_PAGE])
+        .assert()
+        .stdout(expected.to_string());
+}
+
+#[test]
+fn example_number() {
+    let cache = TestCache::with_config("example-number", &[], "[output]\nexample_number = true\n");
+    let expected = fs::read_to_string(TEST_PAGE_RENDER)
+        .unwrap()
+        .replace("    command --opt1 --opt2", "    1. command --opt1 --opt2")
+        .replace(
+            "    command --opt1 placeholder1",
+            "    2. command --opt1 placeholder1",
+        );
+
+    cache
+        .tlrc()
+        .args(["--render", TEST_PAGE])
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn example_prefix_number() {
+    let cache = TestCache::with_config(
+        "example-prefix-number",
+        &[],
+        "[output]\nshow_hyphens = true\nexample_prefix = '[{n}] '\n",
+    );
+    let expected = fs::read_to_string(TEST_PAGE_RENDER)
+        .unwrap()
+        .replace(
+            "  This is a description of a command example:",
+            "  [1] This is a description of a command example:",
+        )
+        .replace("  Another one:", "  [2] Another one:");
+
+    cache
+        .tlrc()
+        .args(["--render", TEST_PAGE])
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn title_with_description() {
+    let cache = TestCache::with_config(
+        "title-with-description",
+        &[],
+        "[output]\ntitle_with_description = true\n",
+    );
+    let expected = fs::read_to_string(TEST_PAGE_TITLE_DESC_RENDER).unwrap();
+
+    cache
+        .tlrc()
+        .args(["--render", TEST_PAGE])
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn table() {
+    let expected = fs::read_to_string(TEST_PAGE_TABLE_RENDER).unwrap();
+    tlrc()
+        .args(["--color", "always", "--render", TEST_PAGE_TABLE])
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn escapes() {
+    let expected = fs::read_to_string(TEST_PAGE_ESCAPES_RENDER).unwrap();
+    tlrc()
+        .args(["--color", "always", "--render", TEST_PAGE_ESCAPES])
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn join_description() {
+    let cache = TestCache::with_config(
+        "join-description",
+        &[],
+        "[output]\njoin_description = true\ntitle_with_description = true\n",
+    );
+    let out = cache.tlrc().args(["--render", TEST_PAGE]).output().unwrap();
+
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.starts_with(
+        "  test page — This is a test page. More information: https://example.org.\n\n"
+    ));
+}
+
+#[test]
+fn highlight_env_vars() {
+    let cache = TestCache::with_config(
+        "highlight-env-vars",
+        &[],
+        "[output]\nhighlight_env_vars = true\n",
+    );
+    let expected = fs::read_to_string(TEST_PAGE_ENV_VARS_RENDER).unwrap();
+
+    cache
+        .tlrc()
+        .args(["--color", "always", "--render", TEST_PAGE_ENV_VARS])
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn theme() {
+    let render_title = |name: &str, config: &str| {
+        let cache = TestCache::with_config(name, &[], config);
+        let out = cache
+            .tlrc()
+            .args(["--color", "always", "--render", TEST_PAGE])
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .nth(1)
+            .unwrap()
+            .to_string()
+    };
+
+    // Options from the config file are applied on top of the theme (bold is kept).
+    assert_eq!(
+        render_title(
+            "theme",
+            "[style]\ntheme = 'monochrome'\n[style.title]\ncolor = 'red'\n"
+        ),
+        render_title(
+            "theme-no-theme",
+            "[style.title]\ncolor = 'red'\nbold = true\n"
+        ),
+    );
+
+    let cache = TestCache::with_config("theme-unknown", &[], "[style]\ntheme = 'abc'\n");
+    let out = cache.tlrc().args(["--render", TEST_PAGE]).output().unwrap();
+    assert_eq!(out.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("gruvbox"));
+}
+
+#[test]
+fn render_large_file() {
+    let cache = TestCache::with_config(
+        "render-large-file",
+        &[],
+        "[output]\nmax_render_bytes = 16\n",
+    );
+    let expected = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+
+    cache
+        .tlrc()
+        .args(["--render", TEST_PAGE])
+        .assert()
+        .stdout(expected);
+
+    let not_a_page = cache.dir.join("not-a-page.log");
+    fs::write(&not_a_page, "some log line\n".repeat(100)).unwrap();
+
+    cache
+        .tlrc()
+        .arg("--render")
+        .arg(&not_a_page)
+        .assert()
+        .failure()
+        .code(5)
+        .stdout("");
+}
+
 #[test]
 fn does_not_exist() {
     tlrc()
@@ -47,3 +623,1092 @@ fn does_not_exist() {
         .assert()
         .failure();
 }
+
+#[test]
+fn list_platform_language() {
+    let cache = TestCache::new(
+        "list-platform-language",
+        &[
+            "en/common/a",
+            "en/linux/b",
+            "en/osx/c",
+            "de/common/d",
+            "de/linux/e",
+            "de/linux/f",
+        ],
+    );
+
+    cache
+        .tlrc()
+        .args(["--list", "--platform", "linux", "--language", "de"])
+        .assert()
+        .stdout("e\nf\n");
+
+    cache
+        .tlrc()
+        .args(["--list", "--platform", "osx", "--language", "de"])
+        .assert()
+        .success()
+        .stdout("");
+
+    cache
+        .tlrc()
+        .args(["--list", "--platform", "linux", "--language", "fr"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn list_language() {
+    let cache = TestCache::new(
+        "list-language",
+        &[
+            "en/common/a",
+            "en/linux/b",
+            "en/osx/e",
+            "de/common/c",
+            "de/osx/d",
+        ],
+    );
+
+    cache
+        .tlrc()
+        .args(["--list", "--language", "de"])
+        .assert()
+        .stdout("c\n");
+    cache
+        .tlrc()
+        .args(["--list-all", "--language", "de"])
+        .assert()
+        .stdout("c\nd\n");
+
+    // English pages are listed if the language is not installed.
+    let out = cache
+        .tlrc()
+        .args(["--list", "--language", "fr"])
+        .output()
+        .unwrap();
+    assert_eq!(out.stdout, b"a\nb\n");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("'fr' is not installed"));
+}
+
+#[test]
+fn count() {
+    let cache = TestCache::new(
+        "count",
+        &[
+            "en/common/a",
+            "en/linux/a",
+            "en/linux/b",
+            "en/osx/c",
+            "de/common/a",
+        ],
+    );
+
+    cache
+        .tlrc()
+        .args(["--count", "--platform", "linux"])
+        .assert()
+        .stdout("2\n");
+    cache
+        .tlrc()
+        .args(["--count", "--platform", "osx", "--language", "de"])
+        .assert()
+        .stdout("1\n");
+    cache.tlrc().args(["--count", "a"]).assert().stdout("3\n");
+    cache
+        .tlrc()
+        .args(["--count", "a", "--language", "en"])
+        .assert()
+        .stdout("2\n");
+    cache.tlrc().args(["--count", "x"]).assert().stdout("0\n");
+}
+
+#[test]
+fn list_new() {
+    let cache = TestCache::new(
+        "list-new",
+        &["en/common/a", "en/linux/b", "en/osx/c", "de/linux/b"],
+    );
+
+    let out = cache.tlrc().args(["--list", "--new"]).output().unwrap();
+    assert!(out.status.success());
+    assert!(out.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("not tracked yet"));
+
+    fs::write(
+        cache.dir.join("cache/new_pages"),
+        "pages.de/linux/b.md\npages.en/common/a.md\npages.en/osx/c.md\n",
+    )
+    .unwrap();
+    cache
+        .tlrc()
+        .args(["--list", "--new", "--platform", "linux"])
+        .assert()
+        .stdout("a\n");
+    cache
+        .tlrc()
+        .args(["--list", "--new", "--platform", "all"])
+        .assert()
+        .stdout("a\nc\n");
+    cache
+        .tlrc()
+        .args(["--list", "--new", "--language", "de"])
+        .assert()
+        .stdout("b\n");
+}
+
+#[test]
+fn pages_dir() {
+    let cache = TestCache::new("pages-dir", &["en/common/a", "en/linux/b"]);
+    let pages_dir = cache.dir.join("cache");
+    let expected = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+
+    tlrc()
+        .arg("--pages-dir")
+        .arg(&pages_dir)
+        .args(["--platform", "linux", "a"])
+        .assert()
+        .stdout(expected);
+
+    tlrc()
+        .arg("--pages-dir")
+        .arg(&pages_dir)
+        .args(["--platform", "linux", "--list"])
+        .assert()
+        .stdout("a\nb\n");
+
+    tlrc()
+        .args(["--pages-dir", "nonexistent", "a"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn diff() {
+    let cache = TestCache::new("diff", &["en/common/a", "en/linux/b", "de/common/a"]);
+    let de = cache.dir.join("cache/pages.de/common/a.md");
+    let page = fs::read_to_string(&de).unwrap();
+    fs::write(&de, page.replace("Another one:", "Noch eins:")).unwrap();
+
+    cache
+        .tlrc()
+        .args(["--diff", "en", "de", "a"])
+        .assert()
+        .stdout(format!(
+            "--- {}\n\
+            +++ {}\n\
+            @@ -1,7 +1,7 @@\n \
+            # test page\n \
+            > This is a test page.\n \
+            > More information: <https://example.org>.\n \
+            - This is a description of a `command` example:\n \
+            `command --opt1 --opt2 {{{{placeholder}}}}`\n\
+            -- Another one:\n\
+            +- Noch eins:\n \
+            `command --opt1 {{{{placeholder1 placeholder2 ...}}}}`\n",
+            cache.dir.join("cache/pages.en/common/a.md").display(),
+            de.display(),
+        ));
+
+    let out = cache
+        .tlrc()
+        .args(["--diff", "en", "pl", "a"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("page 'a' does not exist in language 'pl'.")
+    );
+}
+
+#[test]
+fn info_tsv() {
+    let cache = TestCache::new("info-tsv", &["en/common/a", "en/linux/b", "de/common/a"]);
+    let out = cache
+        .tlrc()
+        .args(["--info", "--format", "tsv"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout.lines().map(|x| x.split('\t').collect()).collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], ["language", "page_count", "disk_bytes"]);
+    assert_eq!(rows[1][..2], ["de", "1"]);
+    assert_eq!(rows[2][..2], ["en", "2"]);
+    assert_eq!(rows[3][..2], ["total", "3"]);
+
+    let size = |row: &[&str]| row[2].parse::<u64>().unwrap();
+    assert_eq!(size(&rows[1]) + size(&rows[2]), size(&rows[3]));
+
+    let out = cache
+        .tlrc()
+        .args(["--info", "--format", "json"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.starts_with(r#"{ "languages": [{ "language": "de", "page_count": 1, "#));
+}
+
+#[test]
+fn info_json_escape() {
+    let cache = TestCache::new("info-json-escape", &["en/common/a", "p\"l/common/a"]);
+
+    let out = cache
+        .tlrc()
+        .args(["--info", "--format", "json"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains(r#"{ "language": "p\"l", "page_count": 1, "#));
+}
+
+#[test]
+fn list_languages_tsv() {
+    let cache = TestCache::with_config(
+        "list-languages-tsv",
+        &["en/common/a", "de/common/a"],
+        "languages = ['pl']\n",
+    );
+
+    cache
+        .tlrc()
+        .args(["--list-languages", "--format", "tsv"])
+        .assert()
+        .stdout(
+            "language\tinstalled\tconfigured\n\
+            de\ttrue\tfalse\n\
+            en\ttrue\ttrue\n\
+            pl\tfalse\ttrue\n",
+        );
+
+    // The plain list is not affected.
+    cache
+        .tlrc()
+        .arg("--list-languages")
+        .assert()
+        .stdout("de\nen\n");
+}
+
+#[test]
+fn list_languages_json() {
+    let cache = TestCache::with_config(
+        "list-languages-json",
+        &["en/common/a"],
+        "languages = ['p\"l']\n",
+    );
+
+    cache
+        .tlrc()
+        .args(["--list-languages", "--format", "json"])
+        .assert()
+        .stdout(
+            r#"{ "languages": [{ "language": "en", "installed": true, "configured": true }, { "language": "p\"l", "installed": false, "configured": true }] }"#
+                .to_string()
+                + "\n",
+        );
+}
+
+#[test]
+fn other_platforms() {
+    let cache = TestCache::new("other-platforms", &["en/linux/a", "en/osx/a"]);
+
+    let out = cache
+        .tlrc()
+        .args(["--platform", "linux", "a"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&out.stderr).contains("1 page(s) found for other platforms"));
+
+    // Without a terminal, --interactive does not ask anything.
+    let interactive = cache
+        .tlrc()
+        .args(["--platform", "linux", "--interactive", "a"])
+        .output()
+        .unwrap();
+    assert_eq!(interactive.stdout, out.stdout);
+    assert!(!String::from_utf8_lossy(&interactive.stderr).contains("Page to show"));
+
+    let out = cache
+        .tlrc()
+        .args(["--platform", "linux", "--no-other-platforms", "a"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert!(out.stderr.is_empty());
+}
+
+#[test]
+fn platform_auto() {
+    let cache = TestCache::new("platform-auto", &["en/linux/a", "en/windows/a"]);
+    let pages = fs::canonicalize(cache.dir.join("cache")).unwrap();
+
+    cache
+        .tlrc()
+        .args(["--where", "--platform", "auto", "a"])
+        .env("WSL_DISTRO_NAME", "Ubuntu")
+        .assert()
+        .stdout(format!(
+            "{}\n{}\n",
+            pages.join("pages.en/windows/a.md").display(),
+            pages.join("pages.en/linux/a.md").display()
+        ));
+}
+
+#[test]
+fn platform_priority() {
+    let cache = TestCache::new("platform-priority", &["en/linux/a", "en/osx/a"]);
+
+    for (platforms, expected) in [("osx,linux", "osx"), ("linux,macos", "linux")] {
+        let out = cache
+            .tlrc()
+            .args(["--verbose", "--platform", platforms, "a"])
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        let found = stderr.lines().find(|l| l.contains("found page:")).unwrap();
+        assert!(found.contains(&format!("{expected}/a.md")));
+    }
+}
+
+#[test]
+fn aliases() {
+    let cache = TestCache::with_config(
+        "aliases",
+        &["en/linux/a", "en/linux/b"],
+        "aliases = { x = 'a', b = 'a' }\n",
+    );
+
+    let out = cache.tlrc().arg("x").output().unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("showing 'a' (alias of 'x')"));
+    assert_eq!(out.stdout, fs::read(TEST_PAGE_RENDER).unwrap());
+
+    // Existing pages take precedence over aliases.
+    let out = cache.tlrc().arg("b").output().unwrap();
+    assert!(out.status.success());
+    assert!(!String::from_utf8_lossy(&out.stderr).contains("alias"));
+
+    cache.tlrc().arg("y").assert().failure();
+}
+
+#[test]
+fn config_platform() {
+    let cache = TestCache::with_config(
+        "config-platform",
+        &["en/linux/a", "en/osx/a"],
+        "platform = 'macos'\n",
+    );
+
+    let out = cache.tlrc().args(["--verbose", "a"]).output().unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let found = stderr.lines().find(|l| l.contains("found page:")).unwrap();
+    assert!(found.contains("osx/a.md"));
+
+    // --platform takes precedence.
+    let out = cache
+        .tlrc()
+        .args(["--verbose", "--platform", "linux", "a"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let found = stderr.lines().find(|l| l.contains("found page:")).unwrap();
+    assert!(found.contains("linux/a.md"));
+}
+
+#[test]
+fn platform_suggestion() {
+    let cache = TestCache::new("platform-suggestion", &["en/linux/a", "en/osx/a"]);
+
+    let out = cache
+        .tlrc()
+        .args(["--platform", "lnux", "a"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("Possible values: linux, osx."));
+    assert!(stderr.contains("Did you mean 'linux'?"));
+
+    let out = cache
+        .tlrc()
+        .args(["--platform", "xyz", "a"])
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8_lossy(&out.stderr).contains("Did you mean"));
+}
+
+#[test]
+fn platform_index() {
+    let cache = TestCache::new("platform-index", &["en/linux/a"]);
+    let cache_dir = cache.dir.join("cache");
+
+    // The index is used if it is newer than pages.en.
+    fs::write(cache_dir.join("platforms"), "linux\nsunos\n").unwrap();
+    cache
+        .tlrc()
+        .arg("--list-platforms")
+        .assert()
+        .stdout("linux\nsunos\n");
+
+    // Adding a platform makes the index stale. Wait a bit, because the modification
+    // time of the directory might not change otherwise.
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    fs::create_dir(cache_dir.join("pages.en").join("osx")).unwrap();
+    cache
+        .tlrc()
+        .arg("--list-platforms")
+        .assert()
+        .stdout("linux\nosx\n");
+}
+
+#[test]
+fn list_all_platforms() {
+    let cache = TestCache::new(
+        "list-all-platforms",
+        &["en/common/a", "en/common/b", "en/linux/b", "en/osx/c"],
+    );
+
+    cache
+        .tlrc()
+        .args(["--list", "--platform", "all"])
+        .assert()
+        .stdout("common:\n  a\n  b\n\nlinux:\n  b\n\nosx:\n  c\n");
+}
+
+#[test]
+fn no_common() {
+    let cache = TestCache::new(
+        "no-common",
+        &["en/common/a", "en/common/b", "en/linux/b", "en/osx/c"],
+    );
+
+    cache
+        .tlrc()
+        .args(["--list", "--platform", "linux"])
+        .assert()
+        .stdout("a\nb\n");
+    cache
+        .tlrc()
+        .args(["--list", "--platform", "linux", "--no-common"])
+        .assert()
+        .stdout("b\n");
+
+    // `common` is still used when the page does not exist anywhere else.
+    let out = cache
+        .tlrc()
+        .args(["--platform", "linux", "--no-common", "a"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("from platform 'common'"));
+
+    let out = cache
+        .tlrc()
+        .args(["--platform", "linux", "a"])
+        .output()
+        .unwrap();
+    assert!(out.stderr.is_empty());
+}
+
+#[test]
+fn multiple_pages() {
+    let cache = TestCache::new(
+        "multiple-pages",
+        &["en/common/a", "en/common/b", "en/linux/c"],
+    );
+    let page = fs::read_to_string(TEST_PAGE_RENDER).unwrap();
+
+    let out = cache.tlrc().args(["--multi", "a", "b"]).output().unwrap();
+    let out = String::from_utf8_lossy(&out.stdout);
+    let (first, second) = out.split_once("  ─").unwrap();
+    assert_eq!(first, page);
+    assert!(second.ends_with(&page));
+
+    // Nothing is printed if one of the pages does not exist.
+    cache
+        .tlrc()
+        .args(["--multi", "a", "d"])
+        .assert()
+        .failure()
+        .stdout("");
+    // Without --multi, this is the page "a-b".
+    cache.tlrc().args(["a", "b"]).assert().failure();
+}
+
+#[test]
+fn verify() {
+    let cache = TestCache::new("verify", &["en/common/a", "en/linux/b", "de/common/c"]);
+    cache.tlrc().arg("--verify").assert().success();
+
+    fs::write(
+        cache.dir.join("cache/pages.de/common/c.md"),
+        "# c\n\nnot a valid line\n",
+    )
+    .unwrap();
+    cache.tlrc().arg("--verify").assert().failure();
+}
+
+#[test]
+fn prune_languages() {
+    let cache = TestCache::with_config(
+        "prune-languages",
+        &["en/common/a", "de/common/a", "fr/common/a"],
+        "languages = ['fr']\n",
+    );
+
+    cache
+        .tlrc()
+        .args(["--prune-languages", "--yes"])
+        .assert()
+        .success();
+
+    assert!(cache.dir.join("cache/pages.en").is_dir());
+    assert!(cache.dir.join("cache/pages.fr").is_dir());
+    assert!(!cache.dir.join("cache/pages.de").exists());
+}
+
+#[test]
+fn page_platform_override() {
+    let cache = TestCache::with_config(
+        "page-platform-override",
+        &["en/linux/a", "en/osx/a", "en/windows/a"],
+        "page_platform_overrides = { a = 'osx' }\n[output]\ntitle_style = 'platform_path'\n",
+    );
+
+    let out = cache
+        .tlrc()
+        .args(["--platform", "windows", "a"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).contains("windows/test page"));
+
+    let out = cache.tlrc().arg("a").output().unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).contains("osx/test page"));
+}
+
+#[test]
+fn platform_title_deprecated() {
+    let cache = TestCache::with_config(
+        "platform-title-deprecated",
+        &["en/linux/a"],
+        "[output]\nplatform_title = true\n",
+    );
+
+    let out = cache.tlrc().arg("a").output().unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("linux/test page"));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("output.platform_title is deprecated"));
+}
+
+#[test]
+fn title_style_full() {
+    let cache = TestCache::with_config(
+        "title-style-full",
+        &["en/linux/a", "de/linux/a"],
+        "[output]\ntitle_style = 'full'\n",
+    );
+
+    let out = cache.tlrc().args(["-L", "de", "a"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).contains("de linux/test page"));
+}
+
+#[test]
+fn cache_dir_env() {
+    let cache = TestCache::new("cache-dir-env", &["en/common/a", "en/linux/b"]);
+
+    tlrc()
+        .env("TLDR_CACHE_DIR", cache.dir.join("cache"))
+        .args(["--offline", "--list-all"])
+        .assert()
+        .stdout("a\nb\n");
+}
+
+#[test]
+fn import() {
+    let cache = TestCache::new("import", &[]);
+    let archive_path = cache.dir.join("tldr-pages.en.zip");
+
+    let mut archive = ZipWriter::new(fs::File::create(&archive_path).unwrap());
+    let options = SimpleFileOptions::default();
+    archive.add_directory("common", options).unwrap();
+    archive.start_file("common/a.md", options).unwrap();
+    archive.write_all(&fs::read(TEST_PAGE).unwrap()).unwrap();
+    archive.start_file("LICENSE.md", options).unwrap();
+    archive.finish().unwrap();
+
+    cache
+        .tlrc()
+        .arg("--import")
+        .arg(&archive_path)
+        .assert()
+        .success();
+    cache.tlrc().arg("--list-all").assert().stdout("a\n");
+
+    // Importing again should not rewrite the page, but remove pages that are not in the archive.
+    let pages = cache.dir.join("cache").join("pages.en").join("common");
+    fs::write(pages.join("stale.md"), "").unwrap();
+    let out = cache
+        .tlrc()
+        .arg("--import")
+        .arg(&archive_path)
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&out.stderr).contains("1 unchanged"));
+    cache.tlrc().arg("--list-all").assert().stdout("a\n");
+
+    let bad_name = cache.dir.join("pages.zip");
+    fs::rename(&archive_path, &bad_name).unwrap();
+    cache
+        .tlrc()
+        .arg("--import")
+        .arg(&bad_name)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn import_corrupted() {
+    let cache = TestCache::new("import-corrupted", &[]);
+    let archive_path = cache.dir.join("tldr-pages.en.zip");
+
+    let mut archive = ZipWriter::new(fs::File::create(&archive_path).unwrap());
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    archive.start_file("common/a.md", options).unwrap();
+    archive.write_all(b"# a\n\n> Page.\n").unwrap();
+    archive.finish().unwrap();
+
+    // Damage the contents of the page, so that the checksum does not match.
+    let mut bytes = fs::read(&archive_path).unwrap();
+    let page_start = bytes.windows(3).position(|w| w == b"# a").unwrap();
+    bytes[page_start] = b'!';
+    fs::write(&archive_path, bytes).unwrap();
+
+    let out = cache
+        .tlrc()
+        .arg("--import")
+        .arg(&archive_path)
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("could not extract 'pages.en'"));
+    assert!(stderr.contains("--clean-cache"));
+}
+
+#[test]
+fn info_disk_usage() {
+    let cache = TestCache::new(
+        "info-disk-usage",
+        &["en/common/a", "en/common/b", "de/common/a"],
+    );
+    let pages = cache.dir.join("cache");
+
+    fs::write(pages.join("pages.en/common/a.md"), [b'a'; 512]).unwrap();
+    fs::write(pages.join("pages.en/common/b.md"), [b'b'; 512]).unwrap();
+    fs::write(pages.join("pages.de/common/a.md"), [b'a'; 1536]).unwrap();
+
+    let out = cache.tlrc().arg("--info").output().unwrap();
+    let out = String::from_utf8_lossy(&out.stdout);
+
+    assert!(out.contains("de    : 1 (1.50 KiB)"));
+    assert!(out.contains("en    : 2 (1.00 KiB)"));
+    assert!(out.contains("total : 3 pages (2.50 KiB)"));
+}
+
+#[test]
+fn quiet_verbose_conflict() {
+    tlrc()
+        .args(["--quiet", "--verbose", "--render", TEST_PAGE])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn json_errors() {
+    let out = tlrc()
+        .args(["--format", "json", "--render", "nonexistent.md"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let line = stderr.lines().last().unwrap();
+    assert!(line.starts_with(r#"{ "error": "'nonexistent.md': "#));
+    assert!(line.ends_with(r#"", "kind": "io" }"#));
+}
+
+#[test]
+fn log_timestamps() {
+    let out = tlrc()
+        .args(["--verbose", "--log-timestamps", "--render", TEST_PAGE])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let line = stderr.lines().next().unwrap();
+
+    // e.g. "2024-07-29T12:34:56Z warning: '/dev/null': not a file, ignoring --config"
+    let (timestamp, msg) = line.split_once(' ').unwrap();
+    assert_eq!(timestamp.len(), 20);
+    assert!(timestamp.ends_with('Z'));
+    assert_eq!(timestamp.as_bytes()[10], b'T');
+    assert!(msg.starts_with("warning:"));
+
+    let out = tlrc()
+        .args(["--verbose", "--render", TEST_PAGE])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&out.stderr).starts_with("warning:"));
+}
+
+#[test]
+fn log_format_json() {
+    let out = tlrc()
+        .args(["--verbose", "--log-format", "json", "--render", TEST_PAGE])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert_eq!(
+        stderr.lines().next().unwrap(),
+        r#"{ "level": "warning", "target": "tldr::config", "message": "'/dev/null': not a file, ignoring --config" }"#
+    );
+    assert!(stderr
+        .lines()
+        .all(|l| l.starts_with("{ \"level\": ") && l.ends_with("\" }")));
+}
+
+#[test]
+fn strict_offline() {
+    let cache = TestCache::new("strict-offline", &["en/linux/a"]);
+    let cache_dir = cache.dir.join("cache");
+    fs::write(
+        cache.config(),
+        format!("[cache]\ndir = '{}'\n", cache_dir.display()),
+    )
+    .unwrap();
+    // The last update was in 1970.
+    fs::write(cache_dir.join("last_update"), "0").unwrap();
+
+    let out = cache.tlrc().arg("a").output().unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("cache is stale"));
+
+    cache
+        .tlrc()
+        .args(["--strict-offline", "a"])
+        .assert()
+        .failure()
+        .code(7);
+
+    // The staleness check is skipped entirely.
+    let out = cache
+        .tlrc()
+        .args(["--no-auto-update", "--strict-offline", "a"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert!(!String::from_utf8_lossy(&out.stderr).contains("cache is stale"));
+}
+
+#[test]
+fn failed_update_is_retried() {
+    let mirror = serve_mirror(&[(
+        "/tldr.sha256sums",
+        "0000000000000000000000000000000000000000000000000000000000000000  tldr-pages.en.zip\n",
+    )]);
+    let cache = TestCache::new("failed-update-retry", &["en/linux/a"]);
+    let cache_dir = cache.dir.join("cache");
+    fs::write(
+        cache.config(),
+        format!(
+            "[cache]\ndir = '{}'\nmirror = '{mirror}'\n",
+            cache_dir.display()
+        ),
+    )
+    .unwrap();
+    // The last update was in 1970.
+    fs::write(cache_dir.join("last_update"), "0").unwrap();
+
+    // The archive does not exist on the mirror, so the update fails.
+    let run = |args: &[&str]| {
+        let out = Command::cargo_bin("tldr")
+            .unwrap()
+            .arg("--config")
+            .arg(cache.config())
+            .args(args)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&out.stderr).into_owned()
+    };
+    let stderr = run(&["a"]);
+    assert!(stderr.contains("cache is stale"));
+    assert!(stderr.contains("could not update 'pages.en'"));
+
+    // The failed language is not retried on every lookup, only on the next update.
+    assert_ne!(
+        fs::read_to_string(cache_dir.join("last_update")).unwrap(),
+        "0"
+    );
+    let stderr = run(&["a"]);
+    assert!(!stderr.contains("cache is stale"));
+    let stderr = run(&["--update"]);
+    assert!(stderr.contains("could not update 'pages.en'"));
+}
+
+#[test]
+fn partial_update_keeps_last_update() {
+    const SUMS: &str =
+        "1111111111111111111111111111111111111111111111111111111111111111  tldr-pages.de.zip\n";
+    let mirror = serve_mirror(&[("/tldr.sha256sums", SUMS)]);
+    let cache = TestCache::new("partial-update", &["en/linux/a", "de/linux/a"]);
+    let cache_dir = cache.dir.join("cache");
+    fs::write(
+        cache.config(),
+        format!(
+            "[cache]\ndir = '{}'\nmirror = '{mirror}'\n",
+            cache_dir.display()
+        ),
+    )
+    .unwrap();
+    fs::write(cache_dir.join("tldr.sha256sums"), SUMS).unwrap();
+    fs::write(cache_dir.join("last_update"), "0").unwrap();
+
+    Command::cargo_bin("tldr")
+        .unwrap()
+        .arg("--config")
+        .arg(cache.config())
+        .args(["--update", "--language", "de"])
+        .assert()
+        .success();
+
+    // English was not checked, so the cache is still stale.
+    assert_eq!(
+        fs::read_to_string(cache_dir.join("last_update")).unwrap(),
+        "0"
+    );
+    let out = cache.tlrc().arg("a").output().unwrap();
+    assert!(String::from_utf8_lossy(&out.stderr).contains("cache is stale"));
+}
+
+#[test]
+fn on_demand() {
+    let mirror = serve_mirror(&[("/pages/common/b.md", "# b\n\n> Downloaded on demand.\n")]);
+    let cache = TestCache::with_config(
+        "on-demand",
+        &["en/linux/a"],
+        &format!("on_demand = true\non_demand_mirror = '{mirror}'\n"),
+    );
+    let cache_dir = cache.dir.join("cache");
+
+    let out = Command::cargo_bin("tldr")
+        .unwrap()
+        .arg("--config")
+        .arg(cache.config())
+        .arg("b")
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Downloaded on demand."));
+    assert!(cache_dir.join("pages.en/common/b.md").is_file());
+
+    // Page names cannot point outside of the cache.
+    let out = Command::cargo_bin("tldr")
+        .unwrap()
+        .arg("--config")
+        .arg(cache.config())
+        .arg("../../x")
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("invalid page name: '../../x'"));
+}
+
+#[test]
+fn check_config() {
+    let cache = TestCache::new("check-config", &[]);
+    // auto_update is enabled by default.
+    fs::write(cache.config(), "[cache]\nmax_age = 0\nlanguages = ['de']\n").unwrap();
+    let out = cache.tlrc().arg("--check-config").output().unwrap();
+    assert!(out.status.success());
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains(&*cache.config().to_string_lossy()));
+    assert!(stdout.contains("Languages: de, en"));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("cache.max_age is 0"));
+
+    let cache = TestCache::with_config("check-config-invalid", &[], "invalid_option = 1\n");
+    cache
+        .tlrc()
+        .arg("--check-config")
+        .assert()
+        .failure()
+        .code(3);
+}
+
+#[test]
+fn system_config() {
+    let cache = TestCache::new("system-config", &["en/linux/a"]);
+    let system_config = cache.dir.join("system.toml");
+    fs::write(
+        &system_config,
+        "[output]\nshow_title = false\nraw_markdown = true\n",
+    )
+    .unwrap();
+    // The user config enables rendering, but keeps show_title from the system config.
+    fs::write(
+        cache.config(),
+        format!(
+            "{}\n[output]\nraw_markdown = false\n",
+            fs::read_to_string(cache.config()).unwrap()
+        ),
+    )
+    .unwrap();
+
+    let out = Command::cargo_bin("tldr")
+        .unwrap()
+        .env("TLRC_SYSTEM_CONFIG", &system_config)
+        .env("TLRC_CONFIG", cache.config())
+        .args(["--offline", "a"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with("  This is a test page."));
+
+    // --config replaces both files.
+    let out = Command::cargo_bin("tldr")
+        .unwrap()
+        .env("TLRC_SYSTEM_CONFIG", &system_config)
+        .arg("--config")
+        .arg(cache.config())
+        .args(["--offline", "a"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with("  test page"));
+}
+
+#[test]
+fn config_warnings() {
+    let cache = TestCache::with_config(
+        "config-warnings",
+        &[],
+        "[output]\nraw_markdown = true\n[style.title]\ncolor = 'red'\n",
+    );
+
+    // Warnings are shown on every run and do not cause errors.
+    let out = cache.tlrc().args(["--render", TEST_PAGE]).output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(out.stdout, fs::read(TEST_PAGE).unwrap());
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("the styles from the config are not used")
+    );
+}
+
+#[test]
+fn invalid_mirror() {
+    let cache = TestCache::with_config("invalid-mirror", &[], "mirror = 'htps://example.org'\n");
+
+    let out = cache.tlrc().args(["--render", TEST_PAGE]).output().unwrap();
+    assert_eq!(out.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("cache.mirror: 'htps://example.org' is not a valid http or https URL."));
+}
+
+#[test]
+fn gen_config_commented() {
+    let out = tlrc()
+        .args(["--gen-config", "--commented"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let commented = String::from_utf8(out.stdout).unwrap();
+    assert!(commented.contains("# Max cache age in hours.\nmax_age = 336\n"));
+    assert!(commented.contains("# Indentation of the title.\ntitle = 2\n"));
+    assert!(commented.contains("# Style for the page title.\n[style.title]\n"));
+
+    // Without comments, the config is the same.
+    let out = tlrc().arg("--gen-config").output().unwrap();
+    let plain = String::from_utf8(out.stdout).unwrap();
+    let uncommented: Vec<&str> = commented.lines().filter(|x| !x.starts_with('#')).collect();
+    assert_eq!(uncommented, plain.lines().collect::<Vec<&str>>());
+}
+
+#[test]
+fn gen_completions() {
+    let out = tlrc().args(["--gen-completions", "fish"]).output().unwrap();
+    let out = String::from_utf8_lossy(&out.stdout);
+
+    assert!(out.contains("-l list-all"));
+    assert!(out.contains("(tldr --offline --list-platforms 2> /dev/null)"));
+
+    tlrc()
+        .args(["--gen-completions", "bash"])
+        .assert()
+        .success();
+    tlrc()
+        .args(["--gen-completions", "sh"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn breadcrumb_title() {
+    let cache = TestCache::with_config(
+        "breadcrumb-title",
+        &[
+            "en/common/test-page",
+            "en/common/test",
+            "en/linux/a",
+            "en/osx/a",
+            "en/windows/a",
+        ],
+        "[output]\nbreadcrumb_title = true\nbreadcrumb_separator = ' > '\n",
+    );
+
+    let out = cache.tlrc().arg("test-page").output().unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with("  test > page\n"));
+
+    // Pages without a hyphen in the name are not affected.
+    let out = cache.tlrc().arg("test").output().unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with("  test page\n"));
+}
+
+#[test]
+fn not_found_command() {
+    let cache = TestCache::with_config(
+        "not-found-command",
+        &["en/linux/a"],
+        "[output]\nnot_found_command = 'echo missing {}'\n",
+    );
+
+    cache
+        .tlrc()
+        .arg("foo")
+        .assert()
+        .success()
+        .stdout("missing foo\n");
+
+    // Found pages are not affected.
+    cache.tlrc().arg("a").assert().success();
+
+    // The command is not run when multiple pages are shown, or when the page is not rendered.
+    cache
+        .tlrc()
+        .args(["--multi", "a", "foo", "a"])
+        .assert()
+        .failure()
+        .stdout("");
+    cache
+        .tlrc()
+        .args(["--url", "foo"])
+        .assert()
+        .failure()
+        .stdout("");
+}